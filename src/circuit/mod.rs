@@ -0,0 +1,438 @@
+use std::fmt;
+
+use ark_ff::Field;
+
+use crate::lagrange::multivariate::multivariate::SparseMLE;
+
+/// A gate of a layered circuit. `Add` and `Mul` read two wires of the next layer down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gate<F: Field> {
+    Add(usize, usize),
+    Mul(usize, usize),
+    Const(F),
+}
+
+impl<F: Field> Gate<F> {
+    /// The wires this gate reads, if any.
+    pub fn inputs(&self) -> Option<(usize, usize)> {
+        match *self {
+            Gate::Add(a, b) | Gate::Mul(a, b) => Some((a, b)),
+            Gate::Const(_) => None,
+        }
+    }
+}
+
+/// A layered arithmetic circuit. `layers[0]` is the output layer and the gates of layer `i` read the
+/// wires of layer `i + 1`; the last layer reads the inputs, which count as layer `num_layers()`.
+///
+/// Gate `g` of a layer is wire `g`, so with the layer padded to `2^k` wires it is the hypercube point
+/// whose bits are `g`, most significant first like `DenseMLE`. Padding wires are zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Circuit<F: Field> {
+    num_inputs: usize,
+    layers: Vec<Vec<Gate<F>>>,
+}
+
+/// The wiring predicates of one layer as sparse MLEs, with one entry per gate.
+///
+/// `add` and `mul` are over `(z, x, y)` with `k_i + 2 k_{i+1}` variables and are 1 at `(g, a, b)` for
+/// a gate `g` reading `a` and `b`. `constants` is over `z` and holds the value of each constant gate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WiringPredicates<F: Field> {
+    pub add: SparseMLE<F>,
+    pub mul: SparseMLE<F>,
+    pub constants: SparseMLE<F>,
+}
+
+impl<F: Field> Circuit<F> {
+    pub fn new(num_inputs: usize, layers: Vec<Vec<Gate<F>>>) -> Self {
+        let circuit = Circuit { num_inputs, layers };
+        for (i, layer) in circuit.layers.iter().enumerate() {
+            assert!(!layer.is_empty(), "layer {} has no gates", i);
+            let below = circuit.layer_size(i + 1);
+            assert!(
+                layer
+                    .iter()
+                    .filter_map(Gate::inputs)
+                    .all(|(a, b)| a < below && b < below),
+                "a gate of layer {} reads a wire past the {} wires below it",
+                i,
+                below
+            );
+        }
+        circuit
+    }
+
+    /// The number of gate layers, not counting the inputs.
+    pub fn num_layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    pub fn layer(&self, i: usize) -> &[Gate<F>] {
+        &self.layers[i]
+    }
+
+    /// The number of wires of layer `i`, where `i = num_layers()` is the input layer.
+    pub fn layer_size(&self, i: usize) -> usize {
+        if i == self.layers.len() {
+            self.num_inputs
+        } else {
+            self.layers[i].len()
+        }
+    }
+
+    /// The number of variables `k_i` indexing layer `i`, padded to `2^k_i` wires.
+    pub fn num_vars(&self, i: usize) -> usize {
+        self.layer_size(i)
+            .max(1)
+            .next_power_of_two()
+            .trailing_zeros() as usize
+    }
+
+    /// The values of every layer, from the outputs at index 0 to the inputs at `num_layers()`.
+    pub fn evaluate(&self, inputs: &[F]) -> Vec<Vec<F>> {
+        assert_eq!(
+            inputs.len(),
+            self.num_inputs,
+            "circuit should get one value per input"
+        );
+        let mut values = vec![inputs.to_vec()];
+        for layer in self.layers.iter().rev() {
+            let below = values.last().unwrap();
+            let current = layer
+                .iter()
+                .map(|gate| match *gate {
+                    Gate::Add(a, b) => below[a] + below[b],
+                    Gate::Mul(a, b) => below[a] * below[b],
+                    Gate::Const(c) => c,
+                })
+                .collect();
+            values.push(current);
+        }
+        values.reverse();
+        values
+    }
+
+    /// `add_i`, `mul_i` and the constants of layer `i`, see `WiringPredicates`.
+    pub fn wiring_predicates(&self, i: usize) -> WiringPredicates<F> {
+        let (k_z, k) = (self.num_vars(i), self.num_vars(i + 1));
+        let mut add = vec![];
+        let mut mul = vec![];
+        let mut constants = vec![];
+        for (g, gate) in self.layers[i].iter().enumerate() {
+            let index = |a: usize, b: usize| (g << (2 * k)) | (a << k) | b;
+            match *gate {
+                Gate::Add(a, b) => add.push((index(a, b), F::one())),
+                Gate::Mul(a, b) => mul.push((index(a, b), F::one())),
+                Gate::Const(c) => constants.push((g, c)),
+            }
+        }
+        WiringPredicates {
+            add: SparseMLE::new(k_z + 2 * k, add),
+            mul: SparseMLE::new(k_z + 2 * k, mul),
+            constants: SparseMLE::new(k_z, constants),
+        }
+    }
+
+    /// Load a circuit from its text format, bottom-up like `CircuitBuilder`:
+    ///
+    /// ```text
+    /// # (x_0 * x_1) + (x_2 * x_3) and x_2 + 5
+    /// inputs 4
+    /// layer
+    /// mul 0 1
+    /// mul 2 3
+    /// add 2 2
+    /// const 5
+    /// layer
+    /// add 0 1
+    /// add 2 3
+    /// ```
+    ///
+    /// Every `layer` line starts a new layer reading the one before it, and the last layer is the
+    /// output. Constants are integers and may be negative.
+    pub fn parse(input: &str) -> Result<Self, ParseCircuitError> {
+        let mut builder: Option<CircuitBuilder<F>> = None;
+        let mut layer_started = false;
+        for (number, line) in input.lines().enumerate().map(|(n, l)| (n + 1, l.trim())) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || ParseCircuitError::InvalidLine {
+                line: number,
+                content: line.into(),
+            };
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let Some(builder) = builder.as_mut() else {
+                match tokens[..] {
+                    ["inputs", n] => {
+                        builder = Some(CircuitBuilder::new(n.parse().map_err(|_| invalid())?));
+                        continue;
+                    }
+                    _ => return Err(ParseCircuitError::MissingInputs),
+                }
+            };
+            if tokens[..] == ["layer"] {
+                if layer_started {
+                    if builder.current.is_empty() {
+                        return Err(ParseCircuitError::EmptyLayer { line: number });
+                    }
+                    builder.next_layer();
+                }
+                layer_started = true;
+                continue;
+            }
+            if !layer_started {
+                return Err(invalid());
+            }
+            let wire = |token: &str| -> Result<usize, ParseCircuitError> {
+                let wire = token.parse().map_err(|_| invalid())?;
+                if wire >= builder.below() {
+                    return Err(ParseCircuitError::WireOutOfRange {
+                        line: number,
+                        wire,
+                        size: builder.below(),
+                    });
+                }
+                Ok(wire)
+            };
+            match tokens[..] {
+                ["add", a, b] => {
+                    let (a, b) = (wire(a)?, wire(b)?);
+                    builder.add(a, b);
+                }
+                ["mul", a, b] => {
+                    let (a, b) = (wire(a)?, wire(b)?);
+                    builder.mul(a, b);
+                }
+                ["const", c] => {
+                    let c: i64 = c.parse().map_err(|_| invalid())?;
+                    let value = F::from(c.unsigned_abs());
+                    builder.constant(if c < 0 { -value } else { value });
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        let builder = builder.ok_or(ParseCircuitError::MissingInputs)?;
+        if builder.current.is_empty() {
+            return Err(ParseCircuitError::EmptyLayer {
+                line: input.lines().count(),
+            });
+        }
+        Ok(builder.build())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCircuitError {
+    /// The first line that is not a comment has to be `inputs <n>`.
+    MissingInputs,
+    InvalidLine {
+        line: usize,
+        content: String,
+    },
+    /// A gate reads a wire that the layer below doesn't have.
+    WireOutOfRange {
+        line: usize,
+        wire: usize,
+        size: usize,
+    },
+    /// A layer without gates, ending at `line`.
+    EmptyLayer {
+        line: usize,
+    },
+}
+
+impl fmt::Display for ParseCircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCircuitError::MissingInputs => write!(f, "missing inputs line"),
+            ParseCircuitError::InvalidLine { line, content } => {
+                write!(f, "line {}: invalid line: {}", line, content)
+            }
+            ParseCircuitError::WireOutOfRange { line, wire, size } => write!(
+                f,
+                "line {}: wire {} out of range, the layer below has {} wires",
+                line, wire, size
+            ),
+            ParseCircuitError::EmptyLayer { line } => {
+                write!(f, "line {}: layer has no gates", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseCircuitError {}
+
+/// Builds a circuit from the inputs up. Gates are added to the current layer and read the layer
+/// below it; `next_layer` finishes the current layer, and the layer being built when calling `build`
+/// becomes the output layer.
+#[derive(Debug, Clone)]
+pub struct CircuitBuilder<F: Field> {
+    num_inputs: usize,
+    // finished layers, from the one reading the inputs upwards
+    layers: Vec<Vec<Gate<F>>>,
+    current: Vec<Gate<F>>,
+}
+
+impl<F: Field> CircuitBuilder<F> {
+    pub fn new(num_inputs: usize) -> Self {
+        CircuitBuilder {
+            num_inputs,
+            layers: vec![],
+            current: vec![],
+        }
+    }
+
+    /// The number of wires the current layer can read.
+    pub fn below(&self) -> usize {
+        self.layers.last().map_or(self.num_inputs, Vec::len)
+    }
+
+    fn push(&mut self, gate: Gate<F>) -> usize {
+        if let Some((a, b)) = gate.inputs() {
+            assert!(
+                a < self.below() && b < self.below(),
+                "the layer below has only {} wires",
+                self.below()
+            );
+        }
+        self.current.push(gate);
+        self.current.len() - 1
+    }
+
+    /// Add a gate computing `a + b` and return its wire.
+    pub fn add(&mut self, a: usize, b: usize) -> usize {
+        self.push(Gate::Add(a, b))
+    }
+
+    /// Add a gate computing `a * b` and return its wire.
+    pub fn mul(&mut self, a: usize, b: usize) -> usize {
+        self.push(Gate::Mul(a, b))
+    }
+
+    /// Add a gate that outputs `c` and return its wire.
+    pub fn constant(&mut self, c: F) -> usize {
+        self.push(Gate::Const(c))
+    }
+
+    pub fn next_layer(&mut self) {
+        assert!(!self.current.is_empty(), "layer has no gates");
+        self.layers.push(std::mem::take(&mut self.current));
+    }
+
+    pub fn build(mut self) -> Circuit<F> {
+        self.next_layer();
+        self.layers.reverse();
+        Circuit::new(self.num_inputs, self.layers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::fields::goldilocks::Goldilocks;
+
+    const EXAMPLE: &str = "# (x_0 * x_1) + (x_2 * x_3) and x_2 + 5
+inputs 4
+layer
+mul 0 1
+mul 2 3
+add 2 2
+const 5
+layer
+add 0 1
+add 2 3
+";
+
+    fn example() -> Circuit<Goldilocks> {
+        let mut builder = CircuitBuilder::new(4);
+        let x01 = builder.mul(0, 1);
+        let x23 = builder.mul(2, 3);
+        let double = builder.add(2, 2);
+        let five = builder.constant(Goldilocks::from(5u64));
+        builder.next_layer();
+        builder.add(x01, x23);
+        builder.add(double, five);
+        builder.build()
+    }
+
+    #[test]
+    fn evaluate_layers() {
+        let circuit = example();
+        assert_eq!(circuit.num_layers(), 2);
+        assert_eq!(circuit.num_vars(0), 1);
+        assert_eq!(circuit.layer(1)[3], Gate::Const(Goldilocks::from(5u64)));
+        let inputs: Vec<Goldilocks> = (1..5u64).map(Goldilocks::from).collect();
+        let values = circuit.evaluate(&inputs);
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[1], [2u64, 12, 6, 5].map(Goldilocks::from).to_vec());
+        assert_eq!(values[0], [14u64, 11].map(Goldilocks::from).to_vec());
+    }
+
+    #[test]
+    fn parse_matches_builder() {
+        assert_eq!(Circuit::parse(EXAMPLE), Ok(example()));
+        assert_eq!(
+            Circuit::<Goldilocks>::parse("layer\nadd 0 1"),
+            Err(ParseCircuitError::MissingInputs)
+        );
+        assert_eq!(
+            Circuit::<Goldilocks>::parse("inputs 2\nlayer\nadd 0 2"),
+            Err(ParseCircuitError::WireOutOfRange {
+                line: 3,
+                wire: 2,
+                size: 2
+            })
+        );
+        assert_eq!(
+            Circuit::<Goldilocks>::parse("inputs 2\nlayer\nlayer\nadd 0 1"),
+            Err(ParseCircuitError::EmptyLayer { line: 3 })
+        );
+        assert_eq!(
+            Circuit::<Goldilocks>::parse("inputs 2\nlayer\nsub 0 1"),
+            Err(ParseCircuitError::InvalidLine {
+                line: 3,
+                content: "sub 0 1".into()
+            })
+        );
+        let negative = Circuit::<Goldilocks>::parse("inputs 1\nlayer\nconst -3").unwrap();
+        assert_eq!(negative.layer(0), &[Gate::Const(-Goldilocks::from(3u64))]);
+    }
+
+    #[test]
+    fn wiring_predicates_match_gates() {
+        let circuit = example();
+        let values = circuit.evaluate(&(1..5u64).map(Goldilocks::from).collect::<Vec<_>>());
+        let (k_z, k) = (circuit.num_vars(1), circuit.num_vars(2));
+        let predicates = circuit.wiring_predicates(1);
+        let (add, mul) = (predicates.add.to_dense(), predicates.mul.to_dense());
+        let constants = predicates.constants.to_dense();
+
+        // W_i(g) = sum_{a, b} add(g, a, b) (W(a) + W(b)) + mul(g, a, b) W(a) W(b) + constant(g)
+        for g in 0..1 << k_z {
+            let mut value = constants.evaluations()[g];
+            for a in 0..circuit.layer_size(2) {
+                for b in 0..circuit.layer_size(2) {
+                    let index = (g << (2 * k)) | (a << k) | b;
+                    let (w_a, w_b) = (values[2][a], values[2][b]);
+                    value += add.evaluations()[index] * (w_a + w_b)
+                        + mul.evaluations()[index] * w_a * w_b;
+                }
+            }
+            assert_eq!(value, values[1][g]);
+        }
+
+        let rng = &mut test_rng();
+        let point: Vec<Goldilocks> = (0..k_z + 2 * k).map(|_| Goldilocks::rand(rng)).collect();
+        assert_eq!(predicates.add.evaluate(&point), add.evaluate(&point));
+        assert_eq!(predicates.mul.evaluate(&point), mul.evaluate(&point));
+    }
+}
@@ -0,0 +1,62 @@
+use ark_ff::{
+    Fp2, Fp2Config, Fp3, Fp3Config, Fp4, Fp4Config, Fp64, MontBackend, MontConfig, MontFp,
+};
+
+/// The BabyBear prime `15 * 2^27 + 1` (RISC Zero, Plonky3).
+#[derive(MontConfig)]
+#[modulus = "2013265921"]
+#[generator = "31"]
+pub struct BabyBearConfig;
+pub type BabyBear = Fp64<MontBackend<BabyBearConfig, 1>>;
+
+// 31 generates the multiplicative group, so it is neither a square nor a cube, and every
+// extension below is a binomial extension by 31.
+
+/// `BabyBear[X] / (X^2 - 31)`
+pub struct BabyBearExt2Config;
+pub type BabyBearExt2 = Fp2<BabyBearExt2Config>;
+
+impl Fp2Config for BabyBearExt2Config {
+    type Fp = BabyBear;
+
+    const NONRESIDUE: BabyBear = MontFp!("31");
+
+    const FROBENIUS_COEFF_FP2_C1: &'static [BabyBear] = &[MontFp!("1"), MontFp!("-1")];
+}
+
+/// `BabyBear[X] / (X^3 - 31)`
+pub struct BabyBearExt3Config;
+pub type BabyBearExt3 = Fp3<BabyBearExt3Config>;
+
+impl Fp3Config for BabyBearExt3Config {
+    type Fp = BabyBear;
+
+    const NONRESIDUE: BabyBear = MontFp!("31");
+
+    const FROBENIUS_COEFF_FP3_C1: &'static [BabyBear] =
+        &[MontFp!("1"), MontFp!("1314723123"), MontFp!("698542797")];
+    const FROBENIUS_COEFF_FP3_C2: &'static [BabyBear] =
+        &[MontFp!("1"), MontFp!("698542797"), MontFp!("1314723123")];
+
+    const TWO_ADICITY: u32 = 27;
+    const TRACE_MINUS_ONE_DIV_TWO: &'static [u64] = &[11952553456339779606, 1];
+    const QUADRATIC_NONRESIDUE_TO_T: BabyBearExt3 =
+        BabyBearExt3::new(MontFp!("553330006"), MontFp!("0"), MontFp!("0"));
+}
+
+/// `BabyBearExt2[Y] / (Y^2 - X)`, i.e. `BabyBear[X] / (X^4 - 31)`
+pub struct BabyBearExt4Config;
+pub type BabyBearExt4 = Fp4<BabyBearExt4Config>;
+
+impl Fp4Config for BabyBearExt4Config {
+    type Fp2Config = BabyBearExt2Config;
+
+    const NONRESIDUE: BabyBearExt2 = BabyBearExt2::new(MontFp!("0"), MontFp!("1"));
+
+    const FROBENIUS_COEFF_FP4_C1: &'static [BabyBear] = &[
+        MontFp!("1"),
+        MontFp!("1728404513"),
+        MontFp!("-1"),
+        MontFp!("284861408"),
+    ];
+}
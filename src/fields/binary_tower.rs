@@ -0,0 +1,672 @@
+use std::{
+    fmt,
+    iter::{Product, Sum},
+    marker::PhantomData,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use ark_ff::{BigInt, Field, Fp, FpConfig, LegendreSymbol, One, SqrtPrecomputation, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
+    CanonicalSerializeWithFlags, Compress, Flags, SerializationError, Valid, Validate,
+};
+use ark_std::{
+    io::{Read, Write},
+    rand::{
+        distributions::{Distribution, Standard},
+        Rng,
+    },
+};
+use zeroize::Zeroize;
+
+/// Arithmetic for `GF(2)`. The element is stored as the integer 0 or 1, not in Montgomery form.
+pub struct Gf2Config;
+pub type Gf2 = Fp<Gf2Config, 1>;
+
+const fn gf2_from_bigint(bit: BigInt<1>) -> Gf2 {
+    Fp(bit, PhantomData)
+}
+
+impl FpConfig<1> for Gf2Config {
+    const MODULUS: BigInt<1> = BigInt([2]);
+    const GENERATOR: Gf2 = gf2_from_bigint(BigInt([1]));
+    const ZERO: Gf2 = gf2_from_bigint(BigInt([0]));
+    const ONE: Gf2 = gf2_from_bigint(BigInt([1]));
+    // the multiplicative group is trivial
+    const TWO_ADICITY: u32 = 0;
+    const TWO_ADIC_ROOT_OF_UNITY: Gf2 = gf2_from_bigint(BigInt([1]));
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<Gf2>> = None;
+
+    fn add_assign(a: &mut Gf2, b: &Gf2) {
+        a.0 .0[0] ^= b.0 .0[0];
+    }
+
+    fn sub_assign(a: &mut Gf2, b: &Gf2) {
+        a.0 .0[0] ^= b.0 .0[0];
+    }
+
+    fn double_in_place(a: &mut Gf2) {
+        a.0 .0[0] = 0;
+    }
+
+    fn neg_in_place(_a: &mut Gf2) {}
+
+    fn mul_assign(a: &mut Gf2, b: &Gf2) {
+        a.0 .0[0] &= b.0 .0[0];
+    }
+
+    fn sum_of_products<const T: usize>(a: &[Gf2; T], b: &[Gf2; T]) -> Gf2 {
+        let bit = a
+            .iter()
+            .zip(b)
+            .fold(0, |acc, (x, y)| acc ^ (x.0 .0[0] & y.0 .0[0]));
+        gf2_from_bigint(BigInt([bit]))
+    }
+
+    fn square_in_place(_a: &mut Gf2) {}
+
+    fn inverse(a: &Gf2) -> Option<Gf2> {
+        (a.0 .0[0] == 1).then_some(*a)
+    }
+
+    fn from_bigint(other: BigInt<1>) -> Option<Gf2> {
+        (other.0[0] < 2).then(|| gf2_from_bigint(other))
+    }
+
+    fn into_bigint(other: Gf2) -> BigInt<1> {
+        other.0
+    }
+}
+
+// The tower of Wiedemann (and Binius): T_0 = GF(2), and T_k = T_{k-1}[X_k] / (X_k^2 + X_{k-1} X_k + 1)
+// with X_0 = 1. An element of T_k is a0 + a1 * X_k for a0, a1 in T_{k-1}, stored as the low and high
+// halves of its 2^k bits, so T_{k-1} sits inside T_k as the elements whose high half is zero.
+
+fn split(a: u128, level: usize) -> (u128, u128) {
+    let half = 1 << (level - 1);
+    (a & ((1 << half) - 1), a >> half)
+}
+
+fn join(a0: u128, a1: u128, level: usize) -> u128 {
+    a0 | (a1 << (1 << (level - 1)))
+}
+
+// a * X_level
+fn mul_by_generator(a: u128, level: usize) -> u128 {
+    if level == 0 {
+        return a;
+    }
+    // (a0 + a1 X) X = a1 + (a0 + a1 X_{k-1}) X
+    let (a0, a1) = split(a, level);
+    join(a1, a0 ^ mul_by_generator(a1, level - 1), level)
+}
+
+fn mul(a: u128, b: u128, level: usize) -> u128 {
+    if level == 0 {
+        return a & b;
+    }
+    let (a0, a1) = split(a, level);
+    let (b0, b1) = split(b, level);
+    // Karatsuba: a0 b1 + a1 b0 = (a0 + a1)(b0 + b1) - a0 b0 - a1 b1
+    let z0 = mul(a0, b0, level - 1);
+    let z2 = mul(a1, b1, level - 1);
+    let z1 = mul(a0 ^ a1, b0 ^ b1, level - 1) ^ z0 ^ z2;
+    // X^2 = X_{k-1} X + 1
+    join(z0 ^ z2, z1 ^ mul_by_generator(z2, level - 1), level)
+}
+
+fn square(a: u128, level: usize) -> u128 {
+    if level == 0 {
+        return a;
+    }
+    // the cross terms cancel in characteristic 2
+    let (a0, a1) = split(a, level);
+    let s0 = square(a0, level - 1);
+    let s2 = square(a1, level - 1);
+    join(s0 ^ s2, mul_by_generator(s2, level - 1), level)
+}
+
+fn inverse(a: u128, level: usize) -> Option<u128> {
+    if a == 0 {
+        return None;
+    }
+    if level == 0 {
+        return Some(1);
+    }
+    // multiply by the conjugate a0 + a1 (X + X_{k-1}), which leaves the norm a0^2 + a0 a1 X_{k-1} + a1^2
+    let (a0, a1) = split(a, level);
+    let a1_generator = mul_by_generator(a1, level - 1);
+    let norm = square(a0, level - 1) ^ mul(a0, a1_generator, level - 1) ^ square(a1, level - 1);
+    let norm_inverse = inverse(norm, level - 1)?;
+    Some(join(
+        mul(a0 ^ a1_generator, norm_inverse, level - 1),
+        mul(a1, norm_inverse, level - 1),
+        level,
+    ))
+}
+
+/// An element of the binary tower field `T_LEVEL` of size `2^(2^LEVEL)`, for `LEVEL` up to 7.
+///
+/// Addition is XOR and multiplication is carry-less, recursing through the tower. Since `2 = 0` here,
+/// round polynomials are interpolated at `univariate::node(t)`, the element with the bits of `t`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BinaryTowerField<const LEVEL: usize>(u128);
+
+pub type BinaryField8b = BinaryTowerField<3>;
+pub type BinaryField16b = BinaryTowerField<4>;
+pub type BinaryField32b = BinaryTowerField<5>;
+pub type BinaryField64b = BinaryTowerField<6>;
+pub type BinaryField128b = BinaryTowerField<7>;
+
+impl<const LEVEL: usize> BinaryTowerField<LEVEL> {
+    const BITS: usize = 1 << LEVEL;
+    const MASK: u128 = u128::MAX >> (128 - Self::BITS);
+    const BYTES: usize = Self::BITS.div_ceil(8);
+
+    pub fn new(bits: u128) -> Self {
+        assert!(LEVEL <= 7, "the tower only goes up to 128 bits");
+        assert_eq!(bits & !Self::MASK, 0, "value has more than 2^LEVEL bits");
+        BinaryTowerField(bits)
+    }
+
+    pub fn bits(&self) -> u128 {
+        self.0
+    }
+
+    /// The same element in a field higher up the tower.
+    pub fn embed<const TARGET: usize>(self) -> BinaryTowerField<TARGET> {
+        assert!(TARGET >= LEVEL, "can only embed into a larger field");
+        BinaryTowerField(self.0)
+    }
+}
+
+impl<const LEVEL: usize> fmt::Debug for BinaryTowerField<LEVEL> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BinaryTowerField<{}>({:#x})", LEVEL, self.0)
+    }
+}
+
+impl<const LEVEL: usize> fmt::Display for BinaryTowerField<LEVEL> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl<const LEVEL: usize> Zero for BinaryTowerField<LEVEL> {
+    fn zero() -> Self {
+        BinaryTowerField(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<const LEVEL: usize> One for BinaryTowerField<LEVEL> {
+    fn one() -> Self {
+        BinaryTowerField(1)
+    }
+}
+
+impl<const LEVEL: usize> Neg for BinaryTowerField<LEVEL> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self
+    }
+}
+
+// addition in characteristic 2 is XOR
+#[allow(clippy::suspicious_op_assign_impl)]
+impl<const LEVEL: usize> AddAssign<&Self> for BinaryTowerField<LEVEL> {
+    fn add_assign(&mut self, other: &Self) {
+        self.0 ^= other.0;
+    }
+}
+
+// and so is subtraction
+#[allow(clippy::suspicious_op_assign_impl)]
+impl<const LEVEL: usize> SubAssign<&Self> for BinaryTowerField<LEVEL> {
+    fn sub_assign(&mut self, other: &Self) {
+        self.0 ^= other.0;
+    }
+}
+
+impl<const LEVEL: usize> MulAssign<&Self> for BinaryTowerField<LEVEL> {
+    fn mul_assign(&mut self, other: &Self) {
+        self.0 = mul(self.0, other.0, LEVEL);
+    }
+}
+
+#[allow(clippy::suspicious_op_assign_impl)]
+impl<const LEVEL: usize> DivAssign<&Self> for BinaryTowerField<LEVEL> {
+    fn div_assign(&mut self, other: &Self) {
+        *self *= &other.inverse().expect("division by zero");
+    }
+}
+
+// the remaining operator impls all go through the `OpAssign<&Self>` ones above
+macro_rules! impl_ops {
+    ($($op:ident, $method:ident, $op_assign:ident, $method_assign:ident;)*) => {$(
+        impl<const LEVEL: usize> $op_assign<Self> for BinaryTowerField<LEVEL> {
+            fn $method_assign(&mut self, other: Self) {
+                self.$method_assign(&other);
+            }
+        }
+
+        impl<'a, const LEVEL: usize> $op_assign<&'a mut Self> for BinaryTowerField<LEVEL> {
+            fn $method_assign(&mut self, other: &'a mut Self) {
+                self.$method_assign(&*other);
+            }
+        }
+
+        impl<const LEVEL: usize> $op<Self> for BinaryTowerField<LEVEL> {
+            type Output = Self;
+
+            fn $method(mut self, other: Self) -> Self {
+                self.$method_assign(&other);
+                self
+            }
+        }
+
+        impl<'a, const LEVEL: usize> $op<&'a Self> for BinaryTowerField<LEVEL> {
+            type Output = Self;
+
+            fn $method(mut self, other: &'a Self) -> Self {
+                self.$method_assign(other);
+                self
+            }
+        }
+
+        impl<'a, const LEVEL: usize> $op<&'a mut Self> for BinaryTowerField<LEVEL> {
+            type Output = Self;
+
+            fn $method(mut self, other: &'a mut Self) -> Self {
+                self.$method_assign(&*other);
+                self
+            }
+        }
+    )*};
+}
+
+impl_ops! {
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+}
+
+impl<const LEVEL: usize> Sum<Self> for BinaryTowerField<LEVEL> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<'a, const LEVEL: usize> Sum<&'a Self> for BinaryTowerField<LEVEL> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<const LEVEL: usize> Product<Self> for BinaryTowerField<LEVEL> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+impl<'a, const LEVEL: usize> Product<&'a Self> for BinaryTowerField<LEVEL> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+// an integer n is n * 1 = n mod 2 in characteristic 2
+macro_rules! impl_from_integer {
+    ($($int:ty),*) => {$(
+        impl<const LEVEL: usize> From<$int> for BinaryTowerField<LEVEL> {
+            fn from(n: $int) -> Self {
+                BinaryTowerField((n & 1) as u128)
+            }
+        }
+    )*};
+}
+
+impl_from_integer!(u128, u64, u32, u16, u8);
+
+impl<const LEVEL: usize> From<bool> for BinaryTowerField<LEVEL> {
+    fn from(b: bool) -> Self {
+        BinaryTowerField(b as u128)
+    }
+}
+
+impl<const LEVEL: usize> Distribution<BinaryTowerField<LEVEL>> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> BinaryTowerField<LEVEL> {
+        BinaryTowerField(rng.gen::<u128>() & BinaryTowerField::<LEVEL>::MASK)
+    }
+}
+
+impl<const LEVEL: usize> Zeroize for BinaryTowerField<LEVEL> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+// little-endian bytes, followed by a byte for the flags if there are any
+impl<const LEVEL: usize> CanonicalSerializeWithFlags for BinaryTowerField<LEVEL> {
+    fn serialize_with_flags<W: Write, F: Flags>(
+        &self,
+        mut writer: W,
+        flags: F,
+    ) -> Result<(), SerializationError> {
+        writer.write_all(&self.0.to_le_bytes()[..Self::BYTES])?;
+        if F::BIT_SIZE > 0 {
+            writer.write_all(&[flags.u8_bitmask()])?;
+        }
+        Ok(())
+    }
+
+    fn serialized_size_with_flags<F: Flags>(&self) -> usize {
+        Self::BYTES + usize::from(F::BIT_SIZE > 0)
+    }
+}
+
+impl<const LEVEL: usize> CanonicalSerialize for BinaryTowerField<LEVEL> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.serialize_with_flags(writer, ark_serialize::EmptyFlags)
+    }
+
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        Self::BYTES
+    }
+}
+
+impl<const LEVEL: usize> CanonicalDeserializeWithFlags for BinaryTowerField<LEVEL> {
+    fn deserialize_with_flags<R: Read, F: Flags>(
+        mut reader: R,
+    ) -> Result<(Self, F), SerializationError> {
+        let mut bytes = [0u8; 16];
+        reader.read_exact(&mut bytes[..Self::BYTES])?;
+        let bits = u128::from_le_bytes(bytes);
+        if bits & !Self::MASK != 0 {
+            return Err(SerializationError::InvalidData);
+        }
+        let flags = if F::BIT_SIZE > 0 {
+            let mut flag_byte = [0u8; 1];
+            reader.read_exact(&mut flag_byte)?;
+            F::from_u8(flag_byte[0]).ok_or(SerializationError::UnexpectedFlags)?
+        } else {
+            F::default()
+        };
+        Ok((BinaryTowerField(bits), flags))
+    }
+}
+
+impl<const LEVEL: usize> Valid for BinaryTowerField<LEVEL> {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl<const LEVEL: usize> CanonicalDeserialize for BinaryTowerField<LEVEL> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        _compress: Compress,
+        _validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Self::deserialize_with_flags::<R, ark_serialize::EmptyFlags>(reader).map(|(x, _)| x)
+    }
+}
+
+impl<const LEVEL: usize> Field for BinaryTowerField<LEVEL> {
+    type BasePrimeField = Gf2;
+    type BasePrimeFieldIter = std::vec::IntoIter<Gf2>;
+
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<Self>> = None;
+    const ZERO: Self = BinaryTowerField(0);
+    const ONE: Self = BinaryTowerField(1);
+
+    fn extension_degree() -> u64 {
+        Self::BITS as u64
+    }
+
+    fn to_base_prime_field_elements(&self) -> Self::BasePrimeFieldIter {
+        (0..Self::BITS)
+            .map(|i| Gf2::from(((self.0 >> i) & 1) as u64))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn from_base_prime_field_elems(elems: &[Gf2]) -> Option<Self> {
+        if elems.len() != Self::BITS {
+            return None;
+        }
+        Some(BinaryTowerField(
+            elems
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (i, bit)| acc | ((!bit.is_zero() as u128) << i)),
+        ))
+    }
+
+    fn from_base_prime_field(elem: Gf2) -> Self {
+        BinaryTowerField(!elem.is_zero() as u128)
+    }
+
+    fn double(&self) -> Self {
+        Self::zero()
+    }
+
+    fn double_in_place(&mut self) -> &mut Self {
+        self.0 = 0;
+        self
+    }
+
+    fn neg_in_place(&mut self) -> &mut Self {
+        self
+    }
+
+    fn from_random_bytes_with_flags<F: Flags>(bytes: &[u8]) -> Option<(Self, F)> {
+        if F::BIT_SIZE > 0 || bytes.len() < Self::BYTES {
+            return None;
+        }
+        let mut buffer = [0u8; 16];
+        buffer[..Self::BYTES].copy_from_slice(&bytes[..Self::BYTES]);
+        Some((
+            BinaryTowerField(u128::from_le_bytes(buffer) & Self::MASK),
+            F::default(),
+        ))
+    }
+
+    // squaring is a bijection in characteristic 2, so everything is a square
+    fn legendre(&self) -> LegendreSymbol {
+        if self.is_zero() {
+            LegendreSymbol::Zero
+        } else {
+            LegendreSymbol::QuadraticResidue
+        }
+    }
+
+    // x^(2^m) = x for m = 2^LEVEL, so x^(2^(m - 1)) is the square root
+    fn sqrt(&self) -> Option<Self> {
+        Some(self.frobenius_map(Self::BITS - 1))
+    }
+
+    fn square(&self) -> Self {
+        BinaryTowerField(square(self.0, LEVEL))
+    }
+
+    fn square_in_place(&mut self) -> &mut Self {
+        self.0 = square(self.0, LEVEL);
+        self
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        inverse(self.0, LEVEL).map(BinaryTowerField)
+    }
+
+    fn inverse_in_place(&mut self) -> Option<&mut Self> {
+        self.0 = inverse(self.0, LEVEL)?;
+        Some(self)
+    }
+
+    fn frobenius_map_in_place(&mut self, power: usize) {
+        for _ in 0..power % Self::BITS {
+            self.square_in_place();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_poly::{
+        multivariate::{SparsePolynomial as SparseMVPolynomial, SparseTerm, Term},
+        DenseMVPolynomial, Polynomial,
+    };
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::lagrange::{multivariate::multivariate::DenseMLE, univariate::univariate::node};
+    use crate::sumcheck::{
+        run_sumcheck, util::util::get_claim, FnOracle, MleProduct, Step, Sumcheck, SumcheckError,
+    };
+
+    fn check_field<const LEVEL: usize>() {
+        let rng = &mut test_rng();
+        for _ in 0..20 {
+            let a = BinaryTowerField::<LEVEL>::rand(rng);
+            let b = BinaryTowerField::<LEVEL>::rand(rng);
+            let c = BinaryTowerField::<LEVEL>::rand(rng);
+            assert_eq!(a * (b + c), a * b + a * c);
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a.square(), a * a);
+            assert_eq!(a.frobenius_map(1 << LEVEL), a);
+            assert_eq!(a.sqrt().unwrap().square(), a);
+            if !b.is_zero() {
+                assert_eq!(a / b * b, a);
+            }
+        }
+    }
+
+    #[test]
+    fn tower_levels_are_fields() {
+        check_field::<1>();
+        check_field::<3>();
+        check_field::<5>();
+        check_field::<7>();
+    }
+
+    #[test]
+    fn gf4_generator() {
+        // X_1^2 = X_1 + 1
+        let x = BinaryTowerField::<1>::new(0b10);
+        assert_eq!(x.square(), BinaryTowerField::<1>::new(0b11));
+        assert_eq!(x * x * x, BinaryTowerField::<1>::one());
+    }
+
+    #[test]
+    fn subfields_embed() {
+        let rng = &mut test_rng();
+        let a = BinaryField8b::rand(rng);
+        let b = BinaryField8b::rand(rng);
+        assert_eq!((a * b).embed::<7>(), a.embed::<7>() * b.embed::<7>());
+        assert_eq!(
+            a.inverse().unwrap().embed::<7>(),
+            a.embed::<7>().inverse().unwrap()
+        );
+    }
+
+    #[test]
+    fn serialization_round_trip() {
+        let a = BinaryField32b::rand(&mut test_rng());
+        let mut bytes = vec![];
+        a.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 4);
+        assert_eq!(
+            BinaryField32b::deserialize_compressed(&bytes[..]).unwrap(),
+            a
+        );
+    }
+
+    #[test]
+    fn sumcheck_over_gf2_128() {
+        let rng = &mut test_rng();
+        // a random polynomial of degree 3 in x_1 and 2 in the other variables
+        let terms = [
+            vec![(0, 3)],
+            vec![(0, 1), (2, 1)],
+            vec![(1, 2), (2, 1), (3, 1)],
+            vec![(3, 1)],
+            vec![],
+        ];
+        let g = SparseMVPolynomial::from_coefficients_vec(
+            4,
+            terms
+                .iter()
+                .map(|term| (BinaryField128b::rand(rng), SparseTerm::new(term.clone())))
+                .collect(),
+        );
+        let claim = get_claim(g.clone());
+        let transcript = run_sumcheck(g.clone(), claim, rng).unwrap();
+        assert_eq!(transcript.round_polynomials.len(), 4);
+
+        assert_eq!(
+            run_sumcheck(g, claim + BinaryField128b::one(), rng),
+            Err(SumcheckError::ClaimMismatch { round: 0 })
+        );
+    }
+
+    #[test]
+    fn interpolation_nodes_are_distinct() {
+        let nodes: Vec<BinaryField8b> = (0..256).map(node).collect();
+        assert_eq!(nodes[0], BinaryField8b::zero());
+        assert_eq!(nodes[1], BinaryField8b::one());
+        for (t, x) in nodes.iter().enumerate() {
+            assert_eq!(x.bits(), t as u128);
+        }
+    }
+
+    #[test]
+    fn mle_product_sumcheck_over_gf2_128() {
+        const V: usize = 4;
+        let rng = &mut test_rng();
+        let factors: Vec<DenseMLE<BinaryField128b>> = (0..3)
+            .map(|_| DenseMLE::new(V, (0..1 << V).map(|_| BinaryField128b::rand(rng)).collect()))
+            .collect();
+        let claim = (0..1 << V)
+            .map(|b| {
+                factors
+                    .iter()
+                    .map(|f| f.evaluations()[b])
+                    .product::<BinaryField128b>()
+            })
+            .sum();
+        let g = MleProduct::new(factors);
+
+        let run = |claim| -> Result<_, SumcheckError> {
+            let oracle_g = g.clone();
+            let oracle = FnOracle(move |point: &[BinaryField128b]| Ok(oracle_g.evaluate(point)));
+            let mut step = Sumcheck::start(g.clone(), oracle, claim)?;
+            let rng = &mut test_rng();
+            loop {
+                match step {
+                    Step::Round(sumcheck) => step = sumcheck.next_round(rng)?,
+                    Step::FinalCheck(sumcheck) => return sumcheck.finish(),
+                }
+            }
+        };
+        let transcript = run(claim).unwrap();
+        assert!(transcript
+            .round_polynomials
+            .iter()
+            .all(|g_i| g_i.degree() <= 3));
+        assert_eq!(
+            run(claim + BinaryField128b::one()),
+            Err(SumcheckError::ClaimMismatch { round: 0 })
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use ark_ff::{
+    Fp2, Fp2Config, Fp3, Fp3Config, Fp4, Fp4Config, Fp64, MontBackend, MontConfig, MontFp,
+};
+
+/// The Goldilocks prime `2^64 - 2^32 + 1` (Plonky2).
+#[derive(MontConfig)]
+#[modulus = "18446744069414584321"]
+#[generator = "7"]
+pub struct GoldilocksConfig;
+pub type Goldilocks = Fp64<MontBackend<GoldilocksConfig, 1>>;
+
+// 7 generates the multiplicative group, so it is neither a square nor a cube, and every
+// extension below is a binomial extension by 7.
+
+/// `Goldilocks[X] / (X^2 - 7)`
+pub struct GoldilocksExt2Config;
+pub type GoldilocksExt2 = Fp2<GoldilocksExt2Config>;
+
+impl Fp2Config for GoldilocksExt2Config {
+    type Fp = Goldilocks;
+
+    const NONRESIDUE: Goldilocks = MontFp!("7");
+
+    const FROBENIUS_COEFF_FP2_C1: &'static [Goldilocks] = &[MontFp!("1"), MontFp!("-1")];
+}
+
+/// `Goldilocks[X] / (X^3 - 7)`
+pub struct GoldilocksExt3Config;
+pub type GoldilocksExt3 = Fp3<GoldilocksExt3Config>;
+
+impl Fp3Config for GoldilocksExt3Config {
+    type Fp = Goldilocks;
+
+    const NONRESIDUE: Goldilocks = MontFp!("7");
+
+    const FROBENIUS_COEFF_FP3_C1: &'static [Goldilocks] = &[
+        MontFp!("1"),
+        MontFp!("18446744065119617025"),
+        MontFp!("4294967295"),
+    ];
+    const FROBENIUS_COEFF_FP3_C2: &'static [Goldilocks] = &[
+        MontFp!("1"),
+        MontFp!("4294967295"),
+        MontFp!("18446744065119617025"),
+    ];
+
+    const TWO_ADICITY: u32 = 32;
+    const TRACE_MINUS_ONE_DIV_TWO: &'static [u64] =
+        &[9223372049739677694, 9223372049739677692, 2147483646];
+    const QUADRATIC_NONRESIDUE_TO_T: GoldilocksExt3 =
+        GoldilocksExt3::new(MontFp!("3607031617444012685"), MontFp!("0"), MontFp!("0"));
+}
+
+/// `GoldilocksExt2[Y] / (Y^2 - X)`, i.e. `Goldilocks[X] / (X^4 - 7)`
+pub struct GoldilocksExt4Config;
+pub type GoldilocksExt4 = Fp4<GoldilocksExt4Config>;
+
+impl Fp4Config for GoldilocksExt4Config {
+    type Fp2Config = GoldilocksExt2Config;
+
+    const NONRESIDUE: GoldilocksExt2 = GoldilocksExt2::new(MontFp!("0"), MontFp!("1"));
+
+    const FROBENIUS_COEFF_FP4_C1: &'static [Goldilocks] = &[
+        MontFp!("1"),
+        MontFp!("281474976710656"),
+        MontFp!("-1"),
+        MontFp!("18446462594437873665"),
+    ];
+}
@@ -0,0 +1,72 @@
+use ark_ff::{
+    Fp2, Fp2Config, Fp3, Fp3Config, Fp64, MontBackend, MontConfig, MontFp, QuadExtConfig,
+    QuadExtField,
+};
+
+/// The Mersenne prime `2^31 - 1` (Circle STARKs, Stwo).
+#[derive(MontConfig)]
+#[modulus = "2147483647"]
+#[generator = "7"]
+pub struct Mersenne31Config;
+pub type Mersenne31 = Fp64<MontBackend<Mersenne31Config, 1>>;
+
+/// The complex extension `Mersenne31[i] / (i^2 + 1)`, which exists because `p = 3 mod 4`.
+pub struct Mersenne31Ext2Config;
+pub type Mersenne31Ext2 = Fp2<Mersenne31Ext2Config>;
+
+impl Fp2Config for Mersenne31Ext2Config {
+    type Fp = Mersenne31;
+
+    const NONRESIDUE: Mersenne31 = MontFp!("-1");
+
+    const FROBENIUS_COEFF_FP2_C1: &'static [Mersenne31] = &[MontFp!("1"), MontFp!("-1")];
+}
+
+/// `Mersenne31[X] / (X^3 - 7)`, where 7 generates the multiplicative group and so isn't a cube.
+pub struct Mersenne31Ext3Config;
+pub type Mersenne31Ext3 = Fp3<Mersenne31Ext3Config>;
+
+impl Fp3Config for Mersenne31Ext3Config {
+    type Fp = Mersenne31;
+
+    const NONRESIDUE: Mersenne31 = MontFp!("7");
+
+    const FROBENIUS_COEFF_FP3_C1: &'static [Mersenne31] =
+        &[MontFp!("1"), MontFp!("1513477735"), MontFp!("634005911")];
+    const FROBENIUS_COEFF_FP3_C2: &'static [Mersenne31] =
+        &[MontFp!("1"), MontFp!("634005911"), MontFp!("1513477735")];
+
+    const TWO_ADICITY: u32 = 1;
+    const TRACE_MINUS_ONE_DIV_TWO: &'static [u64] = &[14987979561499623423, 134217727];
+    const QUADRATIC_NONRESIDUE_TO_T: Mersenne31Ext3 =
+        Mersenne31Ext3::new(MontFp!("-1"), MontFp!("0"), MontFp!("0"));
+}
+
+/// `Mersenne31Ext2[u] / (u^2 - (2 + i))`, the quartic extension of Stwo.
+///
+/// `ark_ff::Fp4Config` needs `X^4 - c` to be irreducible over the prime field, which can't happen
+/// for `p = 3 mod 4`, so this is built directly as a quadratic extension of the complex extension.
+pub struct Mersenne31Ext4Config;
+pub type Mersenne31Ext4 = QuadExtField<Mersenne31Ext4Config>;
+
+impl QuadExtConfig for Mersenne31Ext4Config {
+    type BasePrimeField = Mersenne31;
+    type BaseField = Mersenne31Ext2;
+    type FrobCoeff = Mersenne31Ext2;
+
+    const DEGREE_OVER_BASE_PRIME_FIELD: usize = 4;
+
+    const NONRESIDUE: Mersenne31Ext2 = Mersenne31Ext2::new(MontFp!("2"), MontFp!("1"));
+
+    // (2 + i)^((p^k - 1) / 2) for k = 0..4
+    const FROBENIUS_COEFF_C1: &'static [Mersenne31Ext2] = &[
+        Mersenne31Ext2::new(MontFp!("1"), MontFp!("0")),
+        Mersenne31Ext2::new(MontFp!("21189756"), MontFp!("42379512")),
+        Mersenne31Ext2::new(MontFp!("-1"), MontFp!("0")),
+        Mersenne31Ext2::new(MontFp!("2126293891"), MontFp!("2105104135")),
+    ];
+
+    fn mul_base_field_by_frob_coeff(fe: &mut Mersenne31Ext2, power: usize) {
+        *fe *= &Self::FROBENIUS_COEFF_C1[power % Self::DEGREE_OVER_BASE_PRIME_FIELD];
+    }
+}
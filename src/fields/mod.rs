@@ -0,0 +1,49 @@
+pub mod babybear;
+pub mod binary_tower;
+pub mod goldilocks;
+pub mod mersenne31;
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::Field;
+    use ark_std::test_rng;
+
+    use super::{babybear::*, goldilocks::*, mersenne31::*};
+
+    // the hardcoded constants of an extension are easy to get wrong, and arithmetic mostly
+    // still works with a bad Frobenius coefficient, so check x^p explicitly
+    fn check_extension<E: Field>(degree: usize) {
+        let rng = &mut test_rng();
+        assert_eq!(E::extension_degree(), degree as u64);
+        for _ in 0..10 {
+            let a = E::rand(rng);
+            let b = E::rand(rng);
+            assert_eq!(a * b * b.inverse().unwrap(), a);
+            assert_eq!(a.frobenius_map(1), a.pow(E::characteristic()));
+            assert_eq!(a.frobenius_map(degree), a);
+            let square = a.square();
+            assert_eq!(square.sqrt().unwrap().square(), square);
+        }
+    }
+
+    #[test]
+    fn goldilocks_extensions() {
+        check_extension::<GoldilocksExt2>(2);
+        check_extension::<GoldilocksExt3>(3);
+        check_extension::<GoldilocksExt4>(4);
+    }
+
+    #[test]
+    fn babybear_extensions() {
+        check_extension::<BabyBearExt2>(2);
+        check_extension::<BabyBearExt3>(3);
+        check_extension::<BabyBearExt4>(4);
+    }
+
+    #[test]
+    fn mersenne31_extensions() {
+        check_extension::<Mersenne31Ext2>(2);
+        check_extension::<Mersenne31Ext3>(3);
+        check_extension::<Mersenne31Ext4>(4);
+    }
+}
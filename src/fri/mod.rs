@@ -0,0 +1,583 @@
+use std::fmt;
+
+use ark_ff::FftField;
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial,
+    Radix2EvaluationDomain,
+};
+use ark_std::rand::{Rng, RngCore};
+
+use crate::lagrange::univariate::univariate::interpolate;
+use crate::merkle::{MerkleError, MerkleHasher, MerkleTree, MultiProof};
+
+/// The shape of a FRI proof: the codeword of a polynomial of degree `< degree_bound` is committed
+/// over a subgroup of size `degree_bound * blowup`, folded in half until the degree bound drops to
+/// `final_degree_bound`, and checked at `num_queries` random positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FriParameters {
+    pub degree_bound: usize,
+    pub blowup: usize,
+    pub num_queries: usize,
+    pub final_degree_bound: usize,
+}
+
+impl FriParameters {
+    pub fn new(
+        degree_bound: usize,
+        blowup: usize,
+        num_queries: usize,
+        final_degree_bound: usize,
+    ) -> Self {
+        assert!(
+            degree_bound.is_power_of_two() && final_degree_bound.is_power_of_two(),
+            "degree bounds should be powers of two"
+        );
+        assert!(
+            final_degree_bound < degree_bound,
+            "there should be at least one folding round"
+        );
+        assert!(
+            blowup.is_power_of_two() && blowup > 1,
+            "blowup should be a power of two greater than 1"
+        );
+        assert!(num_queries > 0, "there should be at least one query");
+        FriParameters {
+            degree_bound,
+            blowup,
+            num_queries,
+            final_degree_bound,
+        }
+    }
+
+    /// The number of times the codeword is folded, each committed layer being folded once.
+    pub fn num_rounds(&self) -> usize {
+        (self.degree_bound / self.final_degree_bound).trailing_zeros() as usize
+    }
+
+    /// The size of the subgroup the codeword of layer `round` lives on.
+    pub fn domain_size(&self, round: usize) -> usize {
+        (self.degree_bound * self.blowup) >> round
+    }
+
+    fn domain<F: FftField>(&self, round: usize) -> Radix2EvaluationDomain<F> {
+        Radix2EvaluationDomain::new(self.domain_size(round))
+            .expect("field should have a subgroup of the domain size")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FriError {
+    /// The final polynomial doesn't have degree below the final degree bound.
+    DegreeTooHigh { degree: usize, max_degree: usize },
+    /// Layer `round` was never committed, or its openings are missing or aren't at the queried positions.
+    MalformedOpening { round: usize },
+    /// The openings of layer `round` don't match its root.
+    Merkle { round: usize, error: MerkleError },
+    /// Folding the values opened in layer `round - 1` doesn't give the value opened in layer `round`.
+    FoldMismatch { round: usize, query: usize },
+    /// Folding the values opened in the last layer doesn't give the final polynomial's value.
+    FinalPolynomialMismatch { query: usize },
+    /// A challenge was requested for layer `round` before its root was received.
+    RootMissing { round: usize },
+    /// The root of the next layer or the final polynomial was received before layer `round` was folded.
+    NotFolded { round: usize },
+    /// A challenge or a root was received after all rounds were folded.
+    NoRoundsLeft,
+    /// The queries were checked before the final polynomial was received.
+    FinalPolynomialMissing,
+}
+
+impl fmt::Display for FriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FriError::DegreeTooHigh { degree, max_degree } => write!(
+                f,
+                "final polynomial has degree {}, expected at most {}",
+                degree, max_degree
+            ),
+            FriError::MalformedOpening { round } => {
+                write!(f, "malformed openings in round {}", round)
+            }
+            FriError::Merkle { round, error } => {
+                write!(f, "merkle error in round {}: {}", round, error)
+            }
+            FriError::FoldMismatch { round, query } => write!(
+                f,
+                "query {} is not consistent with the fold into round {}",
+                query, round
+            ),
+            FriError::FinalPolynomialMismatch { query } => {
+                write!(f, "query {} does not match the final polynomial", query)
+            }
+            FriError::RootMissing { round } => {
+                write!(f, "the root of layer {} has not been received", round)
+            }
+            FriError::NotFolded { round } => write!(f, "layer {} has not been folded", round),
+            FriError::NoRoundsLeft => write!(f, "all rounds have been folded"),
+            FriError::FinalPolynomialMissing => {
+                write!(f, "the final polynomial has not been received")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FriError {}
+
+/// Writing `f(x) = f_e(x^2) + x * f_o(x^2)`, the fold `f_e + alpha * f_o` at `x^2`, from `a = f(x)`
+/// and `b = f(-x)`.
+fn fold_pair<F: FftField>(a: F, b: F, x: F, alpha: F) -> F {
+    let two_inv = F::from(2u64).inverse().unwrap();
+    ((a + b) + alpha * (a - b) * x.inverse().unwrap()) * two_inv
+}
+
+// leaf `j` holds `f(w^j)` and `f(-w^j) = f(w^(j + n/2))`, so both values of a fold are opened together
+fn commit_layer<F: FftField, H: MerkleHasher<F>>(codeword: &[F]) -> MerkleTree<F, H> {
+    let (lo, hi) = codeword.split_at(codeword.len() / 2);
+    MerkleTree::new(lo.iter().zip(hi).map(|(&a, &b)| vec![a, b]).collect())
+}
+
+pub struct FriProver<F: FftField, H: MerkleHasher<F>> {
+    params: FriParameters,
+    // the codeword of every layer folded so far, the first `num_rounds` of them committed
+    codewords: Vec<Vec<F>>,
+    trees: Vec<MerkleTree<F, H>>,
+}
+
+impl<F: FftField, H: MerkleHasher<F>> FriProver<F, H> {
+    /// Commit to the Reed-Solomon encoding of `polynomial`, its evaluations over the first domain.
+    pub fn commit(params: FriParameters, polynomial: &DensePolynomial<F>) -> Self {
+        assert!(
+            polynomial.coeffs.len() <= params.degree_bound,
+            "polynomial should be below the degree bound"
+        );
+        let codeword = params.domain::<F>(0).fft(&polynomial.coeffs);
+        Self::from_codeword(params, codeword)
+    }
+
+    /// Commit to the encoding of `message`, read as the evaluations at `0, ..., degree_bound - 1` of the
+    /// polynomial `UnivarInterpolation` extends it with.
+    pub fn from_message(params: FriParameters, message: &[F]) -> Self {
+        assert_eq!(
+            message.len(),
+            params.degree_bound,
+            "message should have degree_bound symbols"
+        );
+        Self::commit(params, &interpolate(message))
+    }
+
+    fn from_codeword(params: FriParameters, codeword: Vec<F>) -> Self {
+        assert_eq!(codeword.len(), params.domain_size(0));
+        let tree = commit_layer(&codeword);
+        FriProver {
+            params,
+            codewords: vec![codeword],
+            trees: vec![tree],
+        }
+    }
+
+    pub fn codeword(&self) -> &[F] {
+        &self.codewords[0]
+    }
+
+    /// The root of the last committed layer.
+    pub fn root(&self) -> &H::Digest {
+        self.trees.last().unwrap().root()
+    }
+
+    /// Fold the last layer with `alpha` and commit to the result, unless this was the last round.
+    pub fn fold(&mut self, alpha: F) {
+        let round = self.codewords.len() - 1;
+        assert!(round < self.params.num_rounds(), "no rounds left to fold");
+        let domain = self.params.domain::<F>(round);
+        let codeword = self.codewords.last().unwrap();
+        let (lo, hi) = codeword.split_at(codeword.len() / 2);
+        let folded: Vec<F> = lo
+            .iter()
+            .zip(hi)
+            .zip(domain.elements())
+            .map(|((&a, &b), x)| fold_pair(a, b, x, alpha))
+            .collect();
+        if round + 1 < self.params.num_rounds() {
+            self.trees.push(commit_layer(&folded));
+        }
+        self.codewords.push(folded);
+    }
+
+    /// The polynomial the fully folded codeword evaluates, sent in the clear.
+    pub fn final_polynomial(&self) -> DensePolynomial<F> {
+        assert_eq!(
+            self.codewords.len(),
+            self.params.num_rounds() + 1,
+            "all rounds should be folded"
+        );
+        let domain = self.params.domain::<F>(self.params.num_rounds());
+        DensePolynomial::from_coefficients_vec(domain.ifft(self.codewords.last().unwrap()))
+    }
+
+    /// Open every committed layer at the positions the queries fold through.
+    pub fn open(&self, queries: &[usize]) -> Vec<MultiProof<F, H::Digest>> {
+        self.trees
+            .iter()
+            .enumerate()
+            .map(|(round, tree)| {
+                let half = self.params.domain_size(round) / 2;
+                let indices: Vec<usize> = queries.iter().map(|query| query % half).collect();
+                tree.open_many(&indices)
+            })
+            .collect()
+    }
+}
+
+pub struct FriVerifier<F: FftField, H: MerkleHasher<F>> {
+    params: FriParameters,
+    roots: Vec<H::Digest>,
+    challenges: Vec<F>,
+    final_polynomial: Option<DensePolynomial<F>>,
+    queries: Vec<usize>,
+}
+
+impl<F: FftField, H: MerkleHasher<F>> FriVerifier<F, H> {
+    pub fn init(params: FriParameters, root: H::Digest) -> Self {
+        FriVerifier {
+            params,
+            roots: vec![root],
+            challenges: vec![],
+            final_polynomial: None,
+            queries: vec![],
+        }
+    }
+
+    /// The challenge to fold the last committed layer with. Fails if its root hasn't been received.
+    pub fn challenge(&mut self, rng: &mut dyn RngCore) -> Result<F, FriError> {
+        let round = self.challenges.len();
+        if round == self.params.num_rounds() {
+            return Err(FriError::NoRoundsLeft);
+        }
+        if self.roots.len() == round {
+            return Err(FriError::RootMissing { round });
+        }
+        let alpha = F::rand(rng);
+        self.challenges.push(alpha);
+        Ok(alpha)
+    }
+
+    /// The root of the next layer, which can only be committed once the last one is folded.
+    pub fn receive_root(&mut self, root: H::Digest) -> Result<(), FriError> {
+        if self.roots.len() == self.params.num_rounds() {
+            return Err(FriError::NoRoundsLeft);
+        }
+        if self.roots.len() > self.challenges.len() {
+            return Err(FriError::NotFolded {
+                round: self.roots.len() - 1,
+            });
+        }
+        self.roots.push(root);
+        Ok(())
+    }
+
+    pub fn receive_final_polynomial(
+        &mut self,
+        polynomial: DensePolynomial<F>,
+    ) -> Result<(), FriError> {
+        if self.challenges.len() != self.params.num_rounds() {
+            return Err(FriError::NotFolded {
+                round: self.challenges.len(),
+            });
+        }
+        if polynomial.degree() >= self.params.final_degree_bound {
+            return Err(FriError::DegreeTooHigh {
+                degree: polynomial.degree(),
+                max_degree: self.params.final_degree_bound - 1,
+            });
+        }
+        self.final_polynomial = Some(polynomial);
+        Ok(())
+    }
+
+    /// Pick `num_queries` positions of the first layer, each a pair `f(w^j), f(-w^j)`.
+    pub fn sample_queries(&mut self, rng: &mut dyn RngCore) -> Vec<usize> {
+        let half = self.params.domain_size(0) / 2;
+        self.queries = (0..self.params.num_queries)
+            .map(|_| rng.gen_range(0..half))
+            .collect();
+        self.queries.clone()
+    }
+
+    /// Check the openings of every committed layer, then follow each query through the folds: the value
+    /// folded from one layer should be opened in the next, and the last one should be on the final polynomial.
+    pub fn verify_queries(&self, openings: &[MultiProof<F, H::Digest>]) -> Result<(), FriError> {
+        // every layer was folded, and so has a root, before the final polynomial was accepted
+        let final_polynomial = self
+            .final_polynomial
+            .as_ref()
+            .ok_or(FriError::FinalPolynomialMissing)?;
+        let num_rounds = self.params.num_rounds();
+        if openings.len() != num_rounds {
+            return Err(FriError::MalformedOpening {
+                round: openings.len().min(num_rounds),
+            });
+        }
+
+        for (round, (opening, root)) in openings.iter().zip(&self.roots).enumerate() {
+            let half = self.params.domain_size(round) / 2;
+            let mut expected: Vec<usize> = self.queries.iter().map(|query| query % half).collect();
+            expected.sort_unstable();
+            expected.dedup();
+            if opening.indices != expected || opening.leaves.iter().any(|leaf| leaf.len() != 2) {
+                return Err(FriError::MalformedOpening { round });
+            }
+            opening
+                .verify::<H>(root, half)
+                .map_err(|error| FriError::Merkle { round, error })?;
+        }
+
+        for (query, &position) in self.queries.iter().enumerate() {
+            let mut index = position;
+            let mut folded = None;
+            for (round, opening) in openings.iter().enumerate() {
+                let domain = self.params.domain::<F>(round);
+                let half = domain.size() / 2;
+                let leaf_index = index % half;
+                // the indices were checked against the queries above
+                let leaf = &opening.leaves[opening.indices.binary_search(&leaf_index).unwrap()];
+                if let Some(value) = folded {
+                    if leaf[index / half] != value {
+                        return Err(FriError::FoldMismatch { round, query });
+                    }
+                }
+                folded = Some(fold_pair(
+                    leaf[0],
+                    leaf[1],
+                    domain.element(leaf_index),
+                    self.challenges[round],
+                ));
+                index = leaf_index;
+            }
+            let x = self.params.domain::<F>(num_rounds).element(index);
+            if folded != Some(final_polynomial.evaluate(&x)) {
+                return Err(FriError::FinalPolynomialMismatch { query });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run FRI for `polynomial` between an honest prover and the verifier.
+pub fn run_fri<F: FftField, H: MerkleHasher<F>>(
+    params: FriParameters,
+    polynomial: &DensePolynomial<F>,
+    rng: &mut dyn RngCore,
+) -> Result<(), FriError> {
+    run(FriProver::<F, H>::commit(params, polynomial), rng)
+}
+
+fn run<F: FftField, H: MerkleHasher<F>>(
+    mut prover: FriProver<F, H>,
+    rng: &mut dyn RngCore,
+) -> Result<(), FriError> {
+    let params = prover.params;
+    let mut verifier = FriVerifier::<F, H>::init(params, prover.root().clone());
+    for round in 0..params.num_rounds() {
+        prover.fold(verifier.challenge(rng)?);
+        if round + 1 < params.num_rounds() {
+            verifier.receive_root(prover.root().clone())?;
+        }
+    }
+    verifier.receive_final_polynomial(prover.final_polynomial())?;
+    let queries = verifier.sample_queries(rng);
+    verifier.verify_queries(&prover.open(&queries))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::fields::goldilocks::Goldilocks;
+    use crate::merkle::Sha256Hasher;
+
+    type Prover = FriProver<Goldilocks, Sha256Hasher>;
+    type Verifier = FriVerifier<Goldilocks, Sha256Hasher>;
+
+    fn random_codeword(size: usize, rng: &mut dyn RngCore) -> Vec<Goldilocks> {
+        (0..size).map(|_| Goldilocks::rand(rng)).collect()
+    }
+
+    #[test]
+    fn fri_accepts_low_degree_polynomials() {
+        let rng = &mut test_rng();
+        for params in [
+            FriParameters::new(16, 2, 8, 1),
+            FriParameters::new(16, 4, 4, 4),
+            FriParameters::new(64, 8, 2, 2),
+        ] {
+            assert_eq!(
+                params.domain_size(params.num_rounds()) / params.blowup,
+                params.final_degree_bound
+            );
+            let polynomial = DensePolynomial::<Goldilocks>::rand(params.degree_bound - 1, rng);
+            assert_eq!(run_fri::<_, Sha256Hasher>(params, &polynomial, rng), Ok(()));
+        }
+    }
+
+    #[test]
+    fn fri_encodes_messages_with_the_lagrange_module() {
+        let rng = &mut test_rng();
+        let params = FriParameters::new(8, 4, 6, 2);
+        let message = [3u64, 1, 4, 1, 5, 9, 2, 6].map(Goldilocks::from);
+        let prover = Prover::from_message(params, &message);
+        let polynomial = interpolate(&message);
+        for (x, value) in params
+            .domain::<Goldilocks>(0)
+            .elements()
+            .zip(prover.codeword())
+        {
+            assert_eq!(polynomial.evaluate(&x), *value);
+        }
+        assert_eq!(run(prover, rng), Ok(()));
+    }
+
+    #[test]
+    fn fri_rejects_high_degree_codeword() {
+        let rng = &mut test_rng();
+        let params = FriParameters::new(16, 2, 8, 2);
+        let prover = Prover::from_codeword(params, random_codeword(32, rng));
+        assert!(matches!(
+            run(prover, rng),
+            Err(FriError::DegreeTooHigh { .. })
+        ));
+    }
+
+    #[test]
+    fn fri_catches_prover_lying_about_the_last_layer() {
+        let rng = &mut test_rng();
+        let params = FriParameters::new(16, 2, 8, 2);
+        let mut prover = Prover::from_codeword(params, random_codeword(32, rng));
+        let mut verifier = Verifier::init(params, prover.root().clone());
+        for round in 0..params.num_rounds() {
+            prover.fold(verifier.challenge(rng).unwrap());
+            if round + 1 < params.num_rounds() {
+                verifier.receive_root(prover.root().clone()).unwrap();
+            }
+        }
+        // claim the last fold is a low degree polynomial
+        let lie = DensePolynomial::rand(params.final_degree_bound - 1, rng);
+        verifier.receive_final_polynomial(lie).unwrap();
+        let queries = verifier.sample_queries(rng);
+        assert!(matches!(
+            verifier.verify_queries(&prover.open(&queries)),
+            Err(FriError::FinalPolynomialMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn fri_catches_inconsistent_folds() {
+        let rng = &mut test_rng();
+        let params = FriParameters::new(16, 2, 8, 1);
+        let polynomial = DensePolynomial::rand(15, rng);
+        let mut prover = Prover::commit(params, &polynomial);
+        let mut verifier = Verifier::init(params, prover.root().clone());
+        prover.fold(verifier.challenge(rng).unwrap());
+        // commit to a different second layer than the fold of the first
+        let fake = params
+            .domain::<Goldilocks>(1)
+            .fft(&DensePolynomial::rand(7, rng).coeffs);
+        prover.trees[1] = commit_layer(&fake);
+        prover.codewords[1] = fake;
+        verifier.receive_root(prover.root().clone()).unwrap();
+        for round in 1..params.num_rounds() {
+            prover.fold(verifier.challenge(rng).unwrap());
+            if round + 1 < params.num_rounds() {
+                verifier.receive_root(prover.root().clone()).unwrap();
+            }
+        }
+        verifier
+            .receive_final_polynomial(prover.final_polynomial())
+            .unwrap();
+        let queries = verifier.sample_queries(rng);
+        assert!(matches!(
+            verifier.verify_queries(&prover.open(&queries)),
+            Err(FriError::FoldMismatch { round: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn fri_rejects_layers_without_a_root() {
+        let rng = &mut test_rng();
+        let params = FriParameters::new(16, 2, 8, 1);
+        let mut prover = Prover::commit(params, &DensePolynomial::rand(15, rng));
+        let mut verifier = Verifier::init(params, prover.root().clone());
+        prover.fold(verifier.challenge(rng).unwrap());
+        // fold again without sending the root of the folded layer
+        assert_eq!(
+            verifier.challenge(rng),
+            Err(FriError::RootMissing { round: 1 })
+        );
+    }
+
+    #[test]
+    fn fri_rejects_out_of_order_calls() {
+        let rng = &mut test_rng();
+        let params = FriParameters::new(8, 2, 2, 2);
+        let mut prover = Prover::commit(params, &DensePolynomial::rand(7, rng));
+        let mut verifier = Verifier::init(params, prover.root().clone());
+        assert_eq!(
+            verifier.receive_root(prover.root().clone()),
+            Err(FriError::NotFolded { round: 0 })
+        );
+        assert_eq!(
+            verifier.receive_final_polynomial(DensePolynomial::from_coefficients_vec(vec![])),
+            Err(FriError::NotFolded { round: 0 })
+        );
+        prover.fold(verifier.challenge(rng).unwrap());
+        verifier.receive_root(prover.root().clone()).unwrap();
+        prover.fold(verifier.challenge(rng).unwrap());
+        assert_eq!(verifier.challenge(rng), Err(FriError::NoRoundsLeft));
+        assert_eq!(
+            verifier.receive_root(prover.root().clone()),
+            Err(FriError::NoRoundsLeft)
+        );
+        let queries = verifier.sample_queries(rng);
+        assert_eq!(
+            verifier.verify_queries(&prover.open(&queries)),
+            Err(FriError::FinalPolynomialMissing)
+        );
+        verifier
+            .receive_final_polynomial(prover.final_polynomial())
+            .unwrap();
+        assert_eq!(verifier.verify_queries(&prover.open(&queries)), Ok(()));
+    }
+
+    #[test]
+    fn fri_rejects_openings_against_another_root() {
+        let rng = &mut test_rng();
+        let params = FriParameters::new(8, 2, 4, 1);
+        let mut prover = Prover::commit(params, &DensePolynomial::rand(7, rng));
+        let other = Prover::commit(params, &DensePolynomial::rand(7, rng));
+        let mut verifier = Verifier::init(params, other.root().clone());
+        for round in 0..params.num_rounds() {
+            prover.fold(verifier.challenge(rng).unwrap());
+            if round + 1 < params.num_rounds() {
+                verifier.receive_root(prover.root().clone()).unwrap();
+            }
+        }
+        verifier
+            .receive_final_polynomial(prover.final_polynomial())
+            .unwrap();
+        let queries = verifier.sample_queries(rng);
+        let mut openings = prover.open(&queries);
+        assert_eq!(
+            verifier.verify_queries(&openings),
+            Err(FriError::Merkle {
+                round: 0,
+                error: MerkleError::RootMismatch
+            })
+        );
+        openings.pop();
+        assert_eq!(
+            verifier.verify_queries(&openings),
+            Err(FriError::MalformedOpening { round: 2 })
+        );
+    }
+}
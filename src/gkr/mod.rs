@@ -0,0 +1,595 @@
+use std::fmt;
+
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+use ark_std::rand::RngCore;
+
+use crate::circuit::{Circuit, Gate};
+use crate::lagrange::multivariate::multivariate::DenseMLE;
+use crate::sumcheck::{
+    PolynomialInfo, Prover, SubClaim, SumcheckError, Verifier, VirtualPolynomial,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GkrError {
+    /// The prover claimed a different number of outputs than the circuit has.
+    WrongNumberOfOutputs {
+        expected: usize,
+        got: usize,
+    },
+    /// The circuit was given a different number of inputs than it has.
+    WrongNumberOfInputs {
+        expected: usize,
+        got: usize,
+    },
+    Sumcheck {
+        layer: usize,
+        error: SumcheckError,
+    },
+    /// The sumcheck of a layer ended in a claim that the wiring predicates and `W_{i+1}(r_x)`,
+    /// `W_{i+1}(r_y)` sent by the prover don't satisfy.
+    WiringCheckFailed {
+        layer: usize,
+    },
+    /// The last two claims disagree with the MLE of the inputs.
+    InputCheckFailed,
+}
+
+impl fmt::Display for GkrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GkrError::WrongNumberOfOutputs { expected, got } => {
+                write!(f, "expected {} outputs, got {}", expected, got)
+            }
+            GkrError::WrongNumberOfInputs { expected, got } => {
+                write!(f, "expected {} inputs, got {}", expected, got)
+            }
+            GkrError::Sumcheck { layer, error } => {
+                write!(f, "sumcheck for layer {} failed: {}", layer, error)
+            }
+            GkrError::WiringCheckFailed { layer } => {
+                write!(f, "wiring check for layer {} failed", layer)
+            }
+            GkrError::InputCheckFailed => write!(f, "claims about the inputs are wrong"),
+        }
+    }
+}
+
+impl std::error::Error for GkrError {}
+
+fn check_inputs<F: Field>(circuit: &Circuit<F>, inputs: &[F]) -> Result<(), GkrError> {
+    if inputs.len() != circuit.num_inputs() {
+        return Err(GkrError::WrongNumberOfInputs {
+            expected: circuit.num_inputs(),
+            got: inputs.len(),
+        });
+    }
+    Ok(())
+}
+
+// the MLE of a layer's values, padded with zeros to 2^k wires
+fn layer_mle<F: Field>(values: &[F], num_vars: usize) -> DenseMLE<F> {
+    let mut evaluations = values.to_vec();
+    evaluations.resize(1 << num_vars, F::zero());
+    DenseMLE::new(num_vars, evaluations)
+}
+
+/// The GKR prover for a layered circuit, holding the values of every layer.
+///
+/// For layer `i` with `k = k_{i+1}` and claims `W_i(z_j) = v_j` combined with coefficients `c_j`,
+/// the prover runs sumcheck over `(x, y)` in `{0,1}^2k` on
+///
+/// `f(x, y) = add(x, y) * (W_{i+1}(x) + W_{i+1}(y)) + mul(x, y) * W_{i+1}(x) * W_{i+1}(y)`
+///
+/// where `add(x, y) = sum_j c_j * add_i(z_j, x, y)` and likewise for `mul`. The sumcheck is split in two
+/// phases: the rounds over `x` prove `sum_x W(x) * (A(x) + M(x)) + A_W(x)` with `A(x) = sum_y add(x, y)`,
+/// `A_W(x) = sum_y add(x, y) * W(y)` and `M(x) = sum_y mul(x, y) * W(y)`, and the rounds over `y` prove
+/// `sum_y f(r_x, y)`. Every table has `2^k` entries and is filled from the sparse wiring predicates,
+/// so a layer takes `O(S_i + S_{i+1})` instead of `O(S^2)`.
+/// Constant gates don't read any wires, so they are left out of `f` and accounted for by the verifier.
+pub struct GkrProver<F: Field> {
+    circuit: Circuit<F>,
+    values: Vec<Vec<F>>,
+    layer: usize,
+    // the add and mul gates of the current layer as (a, b, w_g), weighted by the combined eq
+    add: Vec<(usize, usize, F)>,
+    mul: Vec<(usize, usize, F)>,
+    // r_x, once the rounds over x are done
+    r_x: Vec<F>,
+    sumcheck: Option<Prover<F, VirtualPolynomial<F>>>,
+}
+
+impl<F: Field> GkrProver<F> {
+    pub fn init(circuit: Circuit<F>, inputs: &[F]) -> Result<Self, GkrError> {
+        check_inputs(&circuit, inputs)?;
+        let values = circuit.evaluate(inputs);
+        Ok(GkrProver {
+            circuit,
+            values,
+            layer: 0,
+            add: vec![],
+            mul: vec![],
+            r_x: vec![],
+            sumcheck: None,
+        })
+    }
+
+    pub fn outputs(&self) -> &[F] {
+        &self.values[0]
+    }
+
+    /// Set up the sumcheck for the current layer from the verifier's points and coefficients.
+    pub fn start_layer(&mut self, points: &[Vec<F>], coefficients: &[F]) {
+        let i = self.layer;
+        let k = self.circuit.num_vars(i + 1);
+        // sum_j c_j * eq(z_j, g) for every gate g
+        let weights = DenseMLE::combined_eq_table(points, coefficients);
+        let predicates = self.circuit.wiring_predicates(i);
+        // split the (g, a, b) index of each entry and weight it by w_g
+        let wires = |entries: &[(usize, F)]| -> Vec<(usize, usize, F)> {
+            entries
+                .iter()
+                .map(|&(index, v)| {
+                    let mask = (1 << k) - 1;
+                    (
+                        (index >> k) & mask,
+                        index & mask,
+                        weights.evaluations()[index >> (2 * k)] * v,
+                    )
+                })
+                .collect()
+        };
+        self.add = wires(predicates.add.entries());
+        self.mul = wires(predicates.mul.entries());
+        self.r_x = vec![];
+
+        let below = layer_mle(&self.values[i + 1], k);
+        let mut sum_add = vec![F::zero(); 1 << k];
+        let mut add_w = vec![F::zero(); 1 << k];
+        let mut mul_w = vec![F::zero(); 1 << k];
+        for &(a, b, w) in &self.add {
+            sum_add[a] += w;
+            add_w[a] += w * below.evaluations()[b];
+        }
+        for &(a, b, w) in &self.mul {
+            mul_w[a] += w * below.evaluations()[b];
+        }
+
+        let mut f = VirtualPolynomial::new(k);
+        let w = f.add_mle(below);
+        let sum_add = f.add_mle(DenseMLE::new(k, sum_add));
+        let add_w = f.add_mle(DenseMLE::new(k, add_w));
+        let mul_w = f.add_mle(DenseMLE::new(k, mul_w));
+        f.add_product(F::one(), vec![w, sum_add]);
+        f.add_product(F::one(), vec![add_w]);
+        f.add_product(F::one(), vec![w, mul_w]);
+        self.sumcheck = Some(Prover::init(f).expect("the tables have k variables"));
+    }
+
+    // f(r_x, y) = add(r_x, y) * (W(r_x) + W(y)) + mul(r_x, y) * W(r_x) * W(y) for the rounds over y
+    fn start_rounds_over_y(&mut self) {
+        let k = self.r_x.len();
+        let below = layer_mle(&self.values[self.layer + 1], k);
+        let w_x = below.evaluate(&self.r_x);
+        let eq_x = DenseMLE::eq_table(&self.r_x);
+        let mut add = vec![F::zero(); 1 << k];
+        let mut mul = vec![F::zero(); 1 << k];
+        for &(a, b, w) in &self.add {
+            add[b] += w * eq_x.evaluations()[a];
+        }
+        for &(a, b, w) in &self.mul {
+            mul[b] += w * eq_x.evaluations()[a];
+        }
+
+        let mut f = VirtualPolynomial::new(k);
+        let w_y = f.add_mle(below);
+        let add = f.add_mle(DenseMLE::new(k, add));
+        let mul = f.add_mle(DenseMLE::new(k, mul));
+        f.add_product(w_x, vec![add]);
+        f.add_product(F::one(), vec![add, w_y]);
+        f.add_product(w_x, vec![mul, w_y]);
+        self.sumcheck = Some(Prover::init(f).expect("the tables have k variables"));
+    }
+
+    /// The number of sumcheck rounds for the current layer, `2 k_{i+1}`.
+    pub fn num_rounds(&self) -> usize {
+        2 * self.circuit.num_vars(self.layer + 1)
+    }
+
+    pub fn prove_round(&mut self, r: Option<F>) -> DensePolynomial<F> {
+        let k = self.circuit.num_vars(self.layer + 1);
+        let mut r = r;
+        if self.r_x.len() < k {
+            if let Some(challenge) = r {
+                self.r_x.push(challenge);
+            }
+            if self.r_x.len() == k {
+                // the last challenge over x has been received, so the rounds over y start afresh
+                self.start_rounds_over_y();
+                r = None;
+            }
+        }
+        self.sumcheck
+            .as_mut()
+            .expect("start_layer should be called first")
+            .prove_round(r)
+    }
+
+    /// Send `W_{i+1}(r_x)` and `W_{i+1}(r_y)` for the sumcheck's challenges `(r_x, r_y)`.
+    pub fn finish_layer(&mut self, challenges: &[F]) -> (F, F) {
+        let k = self.circuit.num_vars(self.layer + 1);
+        let below = layer_mle(&self.values[self.layer + 1], k);
+        let (r_x, r_y) = challenges.split_at(k);
+        self.layer += 1;
+        self.sumcheck = None;
+        (below.evaluate(r_x), below.evaluate(r_y))
+    }
+}
+
+/// The GKR verifier. It knows the circuit and the inputs, and only needs `O(S)` time per layer of
+/// size `S` plus `O(n)` for the inputs at the end, instead of evaluating the circuit.
+///
+/// Each layer ends with two claims about `W_{i+1}`, at `r_x` and `r_y`. Rather than restricting
+/// `W_{i+1}` to the line through them, they are combined with a random linear combination that the
+/// next layer's sumcheck proves in one go.
+pub struct GkrVerifier<F: Field> {
+    circuit: Circuit<F>,
+    inputs: Vec<F>,
+    layer: usize,
+    // the claims W_i(z_j) = v_j about the current layer
+    claims: Vec<(Vec<F>, F)>,
+    weights: Vec<F>,
+    claim: F,
+    sumcheck: Option<Verifier<F>>,
+}
+
+impl<F: Field> GkrVerifier<F> {
+    /// Start from the claimed outputs by evaluating their MLE at a random point.
+    pub fn init(
+        circuit: Circuit<F>,
+        inputs: Vec<F>,
+        outputs: &[F],
+        rng: &mut dyn RngCore,
+    ) -> Result<Self, GkrError> {
+        if outputs.len() != circuit.layer_size(0) {
+            return Err(GkrError::WrongNumberOfOutputs {
+                expected: circuit.layer_size(0),
+                got: outputs.len(),
+            });
+        }
+        check_inputs(&circuit, &inputs)?;
+        let k = circuit.num_vars(0);
+        let r: Vec<F> = (0..k).map(|_| F::rand(rng)).collect();
+        let value = layer_mle(outputs, k).evaluate(&r);
+        Ok(GkrVerifier {
+            circuit,
+            inputs,
+            layer: 0,
+            claims: vec![(r, value)],
+            weights: vec![],
+            claim: F::zero(),
+            sumcheck: None,
+        })
+    }
+
+    /// Combine the claims about the current layer as `sum_j c_j W_i(z_j)`, with `c_1 = 1` and the others
+    /// random, and return the points `z_j` and coefficients `c_j` for the prover. The constant gates'
+    /// share `sum_g w_g * c_g` of the sum is computed directly and left out of the sumcheck.
+    pub fn start_layer(&mut self, rng: &mut dyn RngCore) -> (Vec<Vec<F>>, Vec<F>) {
+        let coefficients: Vec<F> = (0..self.claims.len())
+            .map(|j| if j == 0 { F::one() } else { F::rand(rng) })
+            .collect();
+        let points: Vec<Vec<F>> = self.claims.iter().map(|(z, _)| z.clone()).collect();
+        self.weights = DenseMLE::combined_eq_table(&points, &coefficients)
+            .evaluations()
+            .to_vec();
+        let constants: F = self
+            .circuit
+            .layer(self.layer)
+            .iter()
+            .zip(&self.weights)
+            .filter_map(|(gate, w)| match gate {
+                Gate::Const(c) => Some(*c * w),
+                _ => None,
+            })
+            .sum();
+        self.claim = self
+            .claims
+            .iter()
+            .zip(&coefficients)
+            .map(|((_, v), c)| *c * v)
+            .sum::<F>()
+            - constants;
+        self.sumcheck = Some(Verifier::init_deferred(
+            self.claim,
+            PolynomialInfo {
+                num_vars: 2 * self.circuit.num_vars(self.layer + 1),
+                max_degree: 3,
+            },
+        ));
+        (points, coefficients)
+    }
+
+    pub fn verify_round(
+        &mut self,
+        current_poly: DensePolynomial<F>,
+        rng: &mut dyn RngCore,
+    ) -> Result<Option<F>, GkrError> {
+        self.sumcheck
+            .as_mut()
+            .expect("start_layer should be called first")
+            .verify_round(current_poly, rng)
+            .map_err(|error| GkrError::Sumcheck {
+                layer: self.layer,
+                error,
+            })
+    }
+
+    /// Check the sumcheck's final claim `f(r_x, r_y) = v` against the prover's `W_{i+1}(r_x)` and
+    /// `W_{i+1}(r_y)`, which become the claims about the next layer. After the last layer they are
+    /// checked against the inputs.
+    pub fn finish_layer(&mut self, w_x: F, w_y: F) -> Result<(), GkrError> {
+        let layer = self.layer;
+        let sumcheck = self
+            .sumcheck
+            .take()
+            .expect("start_layer should be called first");
+        let k = self.circuit.num_vars(layer + 1);
+        let subclaim = match sumcheck.subclaim() {
+            Some(subclaim) => subclaim.clone(),
+            // a single wire below, so there are no rounds
+            None if k == 0 => SubClaim {
+                point: vec![],
+                expected_value: self.claim,
+            },
+            None => {
+                return Err(GkrError::Sumcheck {
+                    layer,
+                    error: SumcheckError::ProtocolNotFinished,
+                })
+            }
+        };
+        let (r_x, r_y) = subclaim.point.split_at(k);
+
+        let (add, mul) = self.wiring_at(r_x, r_y);
+        if add * (w_x + w_y) + mul * w_x * w_y != subclaim.expected_value {
+            return Err(GkrError::WiringCheckFailed { layer });
+        }
+
+        self.claims = vec![(r_x.to_vec(), w_x), (r_y.to_vec(), w_y)];
+        self.layer += 1;
+        if self.layer == self.circuit.num_layers() {
+            let inputs = layer_mle(&self.inputs, k);
+            if self.claims.iter().any(|(z, v)| inputs.evaluate(z) != *v) {
+                return Err(GkrError::InputCheckFailed);
+            }
+        }
+        Ok(())
+    }
+
+    // sum_g w_g * eq(r_x, a) * eq(r_y, b) over the add and the mul gates (g, a, b) of the layer
+    fn wiring_at(&self, r_x: &[F], r_y: &[F]) -> (F, F) {
+        let eq_x = DenseMLE::eq_table(r_x);
+        let eq_y = DenseMLE::eq_table(r_y);
+        let mut add = F::zero();
+        let mut mul = F::zero();
+        for (gate, w) in self.circuit.layer(self.layer).iter().zip(&self.weights) {
+            let term = |a: usize, b: usize| *w * eq_x.evaluations()[a] * eq_y.evaluations()[b];
+            match *gate {
+                Gate::Add(a, b) => add += term(a, b),
+                Gate::Mul(a, b) => mul += term(a, b),
+                Gate::Const(_) => {}
+            }
+        }
+        (add, mul)
+    }
+}
+
+/// Evaluate `circuit` on `inputs` with an honest prover and verify the outputs it claims with GKR.
+pub fn run_gkr<F: Field>(
+    circuit: &Circuit<F>,
+    inputs: &[F],
+    rng: &mut dyn RngCore,
+) -> Result<Vec<F>, GkrError> {
+    run(
+        GkrProver::init(circuit.clone(), inputs)?,
+        circuit,
+        inputs,
+        rng,
+    )
+}
+
+fn run<F: Field>(
+    mut prover: GkrProver<F>,
+    circuit: &Circuit<F>,
+    inputs: &[F],
+    rng: &mut dyn RngCore,
+) -> Result<Vec<F>, GkrError> {
+    let outputs = prover.outputs().to_vec();
+    let mut verifier = GkrVerifier::init(circuit.clone(), inputs.to_vec(), &outputs, rng)?;
+    for _ in 0..circuit.num_layers() {
+        let (points, coefficients) = verifier.start_layer(rng);
+        prover.start_layer(&points, &coefficients);
+        let mut r = None;
+        let mut challenges = vec![];
+        for _ in 0..prover.num_rounds() {
+            r = verifier.verify_round(prover.prove_round(r), rng)?;
+            challenges.extend(r);
+        }
+        let (w_x, w_y) = prover.finish_layer(&challenges);
+        verifier.finish_layer(w_x, w_y)?;
+    }
+    Ok(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::One;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::fields::goldilocks::Goldilocks;
+
+    // ((x_0 * x_1) + (x_2 * x_3)) * (x_2 + x_3 + x_4), with a padded middle layer of 3 gates
+    fn sample_circuit() -> Circuit<Goldilocks> {
+        Circuit::new(
+            7,
+            vec![
+                vec![Gate::Mul(0, 1)],
+                vec![Gate::Add(0, 1), Gate::Add(2, 3)],
+                vec![
+                    Gate::Mul(0, 1),
+                    Gate::Mul(2, 3),
+                    Gate::Add(2, 3),
+                    Gate::Add(4, 4),
+                ],
+                vec![
+                    Gate::Add(0, 5),
+                    Gate::Add(1, 5),
+                    Gate::Add(2, 5),
+                    Gate::Add(3, 5),
+                    Gate::Mul(4, 6),
+                ],
+            ],
+        )
+    }
+
+    fn sample_inputs() -> Vec<Goldilocks> {
+        // the 0 at index 5 and the 1/2 at index 6 turn the last layer into copies and a halving
+        let mut inputs: Vec<Goldilocks> = (1..6u64).map(Goldilocks::from).collect();
+        inputs.push(Goldilocks::from(0u64));
+        inputs.push(Goldilocks::from(2u64).inverse().unwrap());
+        inputs
+    }
+
+    #[test]
+    fn gkr_verifies_honest_evaluation() {
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let outputs = run_gkr(&circuit, &inputs, &mut test_rng()).unwrap();
+        // (1 * 2 + 3 * 4) * (3 + 4 + 5/2 + 5/2) = 14 * 12
+        assert_eq!(outputs, vec![Goldilocks::from(168u64)]);
+    }
+
+    #[test]
+    fn gkr_with_constant_gates() {
+        // (x_0 * x_1 + 7) * (x_1 + x_1 - 1), loaded from the text format
+        let circuit = Circuit::parse(
+            "inputs 2
+layer
+mul 0 1
+const 7
+add 1 1
+const -1
+layer
+add 0 1
+add 2 3
+layer
+mul 0 1",
+        )
+        .unwrap();
+        let inputs = [3u64, 5].map(Goldilocks::from);
+        let outputs = run_gkr(&circuit, &inputs, &mut test_rng()).unwrap();
+        assert_eq!(outputs, vec![Goldilocks::from(22 * 9u64)]);
+    }
+
+    #[test]
+    fn gkr_with_wide_layers() {
+        // the dense tables would have 4^12 entries for the first layer
+        let n = 1 << 12;
+        let layers = vec![
+            vec![Gate::Add(0, 1)],
+            vec![Gate::Mul(0, n - 1), Gate::Add(1, n - 2)],
+            (0..n).map(|g| Gate::Mul(g, (g + 1) % n)).collect(),
+        ];
+        let circuit = Circuit::new(n, layers);
+        let inputs: Vec<Goldilocks> = (0..n as u64).map(Goldilocks::from).collect();
+        let outputs = run_gkr(&circuit, &inputs, &mut test_rng()).unwrap();
+        assert_eq!(outputs, circuit.evaluate(&inputs)[0]);
+    }
+
+    #[test]
+    fn gkr_rejects_wrong_output() {
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let mut prover = GkrProver::init(circuit.clone(), &inputs).unwrap();
+        prover.values[0][0] += Goldilocks::one();
+        assert_eq!(
+            run(prover, &circuit, &inputs, &mut test_rng()),
+            Err(GkrError::Sumcheck {
+                layer: 0,
+                error: SumcheckError::ClaimMismatch { round: 0 }
+            })
+        );
+    }
+
+    #[test]
+    fn gkr_rejects_wrong_intermediate_layer() {
+        // the outputs are consistent with the wrong layer 1, so the lie only shows one layer down
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let mut prover = GkrProver::init(circuit.clone(), &inputs).unwrap();
+        prover.values[1][1] += Goldilocks::one();
+        prover.values[0][0] = prover.values[1][0] * prover.values[1][1];
+        assert_eq!(
+            run(prover, &circuit, &inputs, &mut test_rng()),
+            Err(GkrError::Sumcheck {
+                layer: 1,
+                error: SumcheckError::ClaimMismatch { round: 0 }
+            })
+        );
+    }
+
+    #[test]
+    fn gkr_rejects_other_inputs() {
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let mut other = inputs.clone();
+        other[4] += Goldilocks::one();
+        let prover = GkrProver::init(circuit.clone(), &other).unwrap();
+        assert_eq!(
+            run(prover, &circuit, &inputs, &mut test_rng()),
+            Err(GkrError::InputCheckFailed)
+        );
+    }
+
+    #[test]
+    fn gkr_rejects_wrong_number_of_inputs() {
+        let circuit = sample_circuit();
+        let inputs = &sample_inputs()[1..];
+        let expected = GkrError::WrongNumberOfInputs {
+            expected: 7,
+            got: 6,
+        };
+        assert_eq!(
+            GkrProver::init(circuit.clone(), inputs).err(),
+            Some(expected.clone())
+        );
+        let outputs = [Goldilocks::one()];
+        assert_eq!(
+            GkrVerifier::init(circuit, inputs.to_vec(), &outputs, &mut test_rng()).err(),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn gkr_rejects_finishing_a_layer_early() {
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let outputs = GkrProver::init(circuit.clone(), &inputs)
+            .unwrap()
+            .outputs()
+            .to_vec();
+        let rng = &mut test_rng();
+        let mut verifier = GkrVerifier::init(circuit, inputs, &outputs, rng).unwrap();
+        verifier.start_layer(rng);
+        // layer 1 has two wires, so the sumcheck has rounds that were never run
+        assert_eq!(
+            verifier.finish_layer(Goldilocks::one(), Goldilocks::one()),
+            Err(GkrError::Sumcheck {
+                layer: 0,
+                error: SumcheckError::ProtocolNotFinished
+            })
+        );
+    }
+}
@@ -1,2 +1,4 @@
+#[allow(clippy::module_inception)]
 pub mod multivariate;
+#[allow(clippy::module_inception)]
 pub mod univariate;
@@ -9,11 +9,13 @@ pub mod multivariate {
         basis: fn(x: [Fp; V], w: [Fp; V]) -> Fp,
     }
 
+    type Interpolation<Fp, const V: usize> =
+        fn(x: [Fp; V], f: fn([u8; V]) -> Fp, bases: &Vec<MultivarBasis<Fp, V>>) -> Fp;
+
     pub struct MulitvarInterpolation<Fp: Field, const V: usize> {
         f: fn(w: [u8; V]) -> Fp,
         bases: Vec<MultivarBasis<Fp, V>>,
-        interpolation:
-            fn(x: [Fp; V], f: fn([u8; V]) -> Fp, bases: &Vec<MultivarBasis<Fp, V>>) -> Fp,
+        interpolation: Interpolation<Fp, V>,
     }
 
     impl<Fp: Field, const V: usize> MulitvarInterpolation<Fp, V> {
@@ -22,7 +24,7 @@ pub mod multivariate {
             let bases: Vec<MultivarBasis<Fp, V>> = (0..V)
                 .map(|_| 0..2u8)
                 .multi_cartesian_product()
-                .map(|w| MultivarBasis::from(w))
+                .map(MultivarBasis::from)
                 .collect();
 
             MulitvarInterpolation {
@@ -46,13 +48,7 @@ pub mod multivariate {
         pub fn new(w: [F; N]) -> Self {
             MultivarBasis {
                 w,
-                basis: |x: [F; N], w: [F; N]| {
-                    let mut accumulator: F = F::one();
-                    for (x_i, w_i) in x.iter().zip(w) {
-                        accumulator *= (w_i * x_i) + (F::one() - w_i) * (F::one() - x_i);
-                    }
-                    accumulator
-                },
+                basis: |x: [F; N], w: [F; N]| eq(&x, &w),
             }
         }
         pub fn evaluate(&self, x: [F; N]) -> F {
@@ -60,6 +56,145 @@ pub mod multivariate {
         }
     }
 
+    /// The multilinear Lagrange basis polynomial for `w`, evaluated at `x`:
+    /// `eq(x, w) = prod_i (w_i * x_i + (1 - w_i) * (1 - x_i))`, which is 1 at `x = w` and 0 elsewhere on the hypercube.
+    pub fn eq<F: Field>(x: &[F], w: &[F]) -> F {
+        assert_eq!(x.len(), w.len(), "points should have the same dimension");
+        let mut accumulator: F = F::one();
+        for (x_i, w_i) in x.iter().zip(w) {
+            accumulator *= (*w_i * x_i) + (F::one() - w_i) * (F::one() - x_i);
+        }
+        accumulator
+    }
+
+    /// Multilinear extension stored as its table of evaluations over the boolean hypercube {0,1}^num_vars.
+    /// The table is ordered the same way as `multi_cartesian_product` over `0..2`, i.e. `x_1` is the most significant bit.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DenseMLE<F: Field> {
+        num_vars: usize,
+        evaluations: Vec<F>,
+    }
+
+    impl<F: Field> DenseMLE<F> {
+        pub fn new(num_vars: usize, evaluations: Vec<F>) -> Self {
+            assert_eq!(
+                evaluations.len(),
+                1 << num_vars,
+                "table should have 2^num_vars evaluations"
+            );
+            DenseMLE {
+                num_vars,
+                evaluations,
+            }
+        }
+
+        /// The table of `eq(b, w)` for every `b` in the hypercube, built one variable at a time in O(2^n).
+        pub fn eq_table(w: &[F]) -> Self {
+            let mut evaluations = vec![F::one()];
+            for w_i in w {
+                evaluations = evaluations
+                    .iter()
+                    .flat_map(|&e| [e * (F::one() - w_i), e * w_i])
+                    .collect();
+            }
+            DenseMLE::new(w.len(), evaluations)
+        }
+
+        /// The table of `sum_j c_j * eq(z_j, b)`, which combines claims about the same MLE at the points `z_j`.
+        pub fn combined_eq_table(points: &[Vec<F>], coefficients: &[F]) -> Self {
+            assert!(!points.is_empty(), "there should be a point to combine");
+            let num_vars = points[0].len();
+            let mut evaluations = vec![F::zero(); 1 << num_vars];
+            for (z, c) in points.iter().zip(coefficients) {
+                assert_eq!(z.len(), num_vars, "points should have the same dimension");
+                for (e, eq_z) in evaluations.iter_mut().zip(Self::eq_table(z).evaluations()) {
+                    *e += *c * eq_z;
+                }
+            }
+            DenseMLE::new(num_vars, evaluations)
+        }
+
+        pub fn num_vars(&self) -> usize {
+            self.num_vars
+        }
+
+        pub fn evaluations(&self) -> &[F] {
+            &self.evaluations
+        }
+
+        /// Bind `x_1` to `r`, returning the MLE over the remaining `num_vars - 1` variables.
+        /// Each pair `(f(0, x'), f(1, x'))` is folded to `f(0, x') + r * (f(1, x') - f(0, x'))`.
+        pub fn fix_first_variable(&self, r: F) -> Self {
+            assert!(self.num_vars > 0, "no variables left to fix");
+            let half = self.evaluations.len() / 2;
+            let (lo, hi) = self.evaluations.split_at(half);
+            DenseMLE {
+                num_vars: self.num_vars - 1,
+                evaluations: lo.iter().zip(hi).map(|(&a, &b)| a + r * (b - a)).collect(),
+            }
+        }
+
+        /// Evaluate the MLE at `point` by fixing the variables one by one.
+        pub fn evaluate(&self, point: &[F]) -> F {
+            assert_eq!(point.len(), self.num_vars, "point has the wrong dimension");
+            let mut table = self.clone();
+            for r in point {
+                table = table.fix_first_variable(*r);
+            }
+            table.evaluations[0]
+        }
+    }
+
+    /// Multilinear extension given by the nonzero entries of its table, as `(index, value)` pairs indexed
+    /// like `DenseMLE`. Meant for tables with few nonzero entries out of `2^num_vars`, like wiring predicates.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct SparseMLE<F: Field> {
+        num_vars: usize,
+        entries: Vec<(usize, F)>,
+    }
+
+    impl<F: Field> SparseMLE<F> {
+        pub fn new(num_vars: usize, entries: Vec<(usize, F)>) -> Self {
+            assert!(
+                entries.iter().all(|&(index, _)| index < 1 << num_vars),
+                "entries should be in the 2^num_vars table"
+            );
+            SparseMLE { num_vars, entries }
+        }
+
+        pub fn num_vars(&self) -> usize {
+            self.num_vars
+        }
+
+        pub fn entries(&self) -> &[(usize, F)] {
+            &self.entries
+        }
+
+        /// `sum_(b, v) v * eq(point, b)` in O(num_vars) per entry.
+        pub fn evaluate(&self, point: &[F]) -> F {
+            assert_eq!(point.len(), self.num_vars, "point has the wrong dimension");
+            self.entries
+                .iter()
+                .map(|&(index, value)| {
+                    let bits: Vec<F> = (0..self.num_vars)
+                        .rev()
+                        .map(|j| F::from(((index >> j) & 1) as u64))
+                        .collect();
+                    value * eq(point, &bits)
+                })
+                .sum()
+        }
+
+        /// The full table, where repeated indices are added up.
+        pub fn to_dense(&self) -> DenseMLE<F> {
+            let mut evaluations = vec![F::zero(); 1 << self.num_vars];
+            for &(index, value) in &self.entries {
+                evaluations[index] += value;
+            }
+            DenseMLE::new(self.num_vars, evaluations)
+        }
+    }
+
     impl<F: Field, const N: usize> From<Vec<u8>> for MultivarBasis<F, N> {
         fn from(w: Vec<u8>) -> Self {
             let x = w
@@ -75,8 +210,8 @@ pub mod multivariate {
 
 #[cfg(test)]
 mod tests {
-    use super::multivariate::{MulitvarInterpolation, MultivarBasis};
-    use ark_ff::{Fp128, Fp64, MontBackend, MontConfig, One, Zero};
+    use super::multivariate::{eq, DenseMLE, MulitvarInterpolation, MultivarBasis};
+    use ark_ff::{Fp64, MontBackend, MontConfig, One, Zero};
 
     #[derive(MontConfig)]
     #[modulus = "5"]
@@ -115,7 +250,7 @@ mod tests {
                 [1, 1] => Fq5::from(4),
                 _ => panic!("invalid input"),
             }
-        };
+        }
         let interpolation = MulitvarInterpolation::<Fq5, 2>::new(example_fn);
         assert_eq!(interpolation.interpolate([Fq5::zero(), Fq5::zero()]), Fq5::one());
         assert_eq!(interpolation.interpolate([Fq5::zero(), Fq5::one()]), Fq5::from(2u8));
@@ -124,4 +259,63 @@ mod tests {
         assert_eq!(interpolation.interpolate([Fq5::from(3), Fq5::from(4)]), Fq5::from(4u8));
         assert_eq!(interpolation.interpolate([Fq5::from(4), Fq5::from(4)]), Fq5::from(2u8));
     }
+
+    #[test]
+    fn dense_mle_matches_interpolation() {
+        fn example_fn(x: [u8; 2]) -> Fq5 {
+            match x {
+                [0, 0] => Fq5::from(1),
+                [0, 1] => Fq5::from(2),
+                [1, 0] => Fq5::from(1),
+                [1, 1] => Fq5::from(4),
+                _ => panic!("invalid input"),
+            }
+        }
+        let interpolation = MulitvarInterpolation::<Fq5, 2>::new(example_fn);
+        let mle = DenseMLE::new(
+            2,
+            vec![Fq5::from(1), Fq5::from(2), Fq5::from(1), Fq5::from(4)],
+        );
+        for x in 0..5u8 {
+            for y in 0..5u8 {
+                let point = [Fq5::from(x), Fq5::from(y)];
+                assert_eq!(mle.evaluate(&point), interpolation.interpolate(point));
+            }
+        }
+        assert_eq!(
+            mle.fix_first_variable(Fq5::one()).evaluations(),
+            &[Fq5::from(1), Fq5::from(4)]
+        );
+    }
+
+    #[test]
+    fn eq_table_matches_basis() {
+        let w = [Fq5::from(2), Fq5::from(3)];
+        let table = DenseMLE::eq_table(&w);
+        for (b, value) in [[0u8, 0], [0, 1], [1, 0], [1, 1]]
+            .iter()
+            .zip(table.evaluations())
+        {
+            let x = [Fq5::from(b[0]), Fq5::from(b[1])];
+            assert_eq!(*value, MultivarBasis::new(x).evaluate(w));
+            assert_eq!(*value, eq(&x, &w));
+        }
+        let r = [Fq5::from(4), Fq5::from(1)];
+        assert_eq!(table.evaluate(&r), eq(&r, &w));
+    }
+
+    #[test]
+    fn combined_eq_table_is_linear() {
+        let points = vec![
+            vec![Fq5::from(2), Fq5::from(3)],
+            vec![Fq5::from(4), Fq5::from(1)],
+        ];
+        let coefficients = [Fq5::from(1), Fq5::from(3)];
+        let table = DenseMLE::combined_eq_table(&points, &coefficients);
+        let x = [Fq5::from(3), Fq5::from(2)];
+        assert_eq!(
+            table.evaluate(&x),
+            eq(&x, &points[0]) + coefficients[1] * eq(&x, &points[1])
+        );
+    }
 }
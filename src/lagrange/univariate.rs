@@ -1,5 +1,6 @@
 pub mod univariate {
     use ark_ff::Field;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
     /// Univariate Lagrange Basis polynomial
     #[derive(Debug)]
     pub struct UnivarBasis<F: Field> {
@@ -73,12 +74,79 @@ pub mod univariate {
             (self.interpolation)(x, &self.a, &self.bases)
         }
     }
+
+    /// The `t`-th interpolation node, which is `t` itself whenever the characteristic is larger than `t`.
+    /// Otherwise the base-`p` digits of `t` become the coordinates over the prime field, so the nodes
+    /// stay distinct, e.g. in a binary field `node(t)` has the bit pattern of `t`.
+    pub fn node<F: Field>(t: usize) -> F {
+        let characteristic = F::characteristic();
+        let p = characteristic[0];
+        if characteristic[1..].iter().any(|&limb| limb != 0) || p > t as u64 {
+            return F::from(t as u64);
+        }
+        let mut rest = t as u64;
+        let digits: Vec<F::BasePrimeField> = (0..F::extension_degree())
+            .map(|_| {
+                let digit = rest % p;
+                rest /= p;
+                F::BasePrimeField::from(digit)
+            })
+            .collect();
+        assert_eq!(rest, 0, "the field should have more than t elements");
+        F::from_base_prime_field_elems(&digits).expect("one digit per coordinate")
+    }
+
+    /// Recover the coefficients of the unique polynomial of degree at most `evals.len() - 1`
+    /// that takes the value `evals[i]` at the point `node(i)`, by summing `evals[i] * L_i(X)`.
+    pub fn interpolate<F: Field>(evals: &[F]) -> DensePolynomial<F> {
+        let points: Vec<F> = (0..evals.len()).map(node).collect();
+        interpolate_at_points(&points, evals)
+    }
+
+    /// Same as `interpolate`, but the polynomial takes the value `evals[i]` at `points[i]`.
+    /// Every `L_i(X) = Z(X) / ((X - x_i) * Z'(x_i))` is divided out of `Z(X) = prod_j (X - x_j)`, so this is O(n^2).
+    pub fn interpolate_at_points<F: Field>(points: &[F], evals: &[F]) -> DensePolynomial<F> {
+        assert_eq!(points.len(), evals.len(), "need one evaluation per point");
+        let n = points.len();
+        // coefficients of Z(X), lowest degree first
+        let mut vanishing = vec![F::one()];
+        for x_j in points {
+            vanishing.insert(0, F::zero());
+            for k in 0..vanishing.len() - 1 {
+                let shifted = vanishing[k + 1];
+                vanishing[k] -= shifted * x_j;
+            }
+        }
+
+        let mut coeffs = vec![F::zero(); n];
+        for (i, (x_i, eval)) in points.iter().zip(evals).enumerate() {
+            // synthetic division Z(X) / (X - x_i)
+            let mut quotient = vec![F::zero(); n];
+            let mut carry = F::zero();
+            for k in (0..n).rev() {
+                carry = vanishing[k + 1] + carry * x_i;
+                quotient[k] = carry;
+            }
+            let denominator: F = points
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, x_j)| *x_i - x_j)
+                .product();
+            let scale = *eval * denominator.inverse().expect("points should be distinct");
+            for (c, q) in coeffs.iter_mut().zip(&quotient) {
+                *c += scale * q;
+            }
+        }
+        DensePolynomial::from_coefficients_vec(coeffs)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::univariate::{UnivarBasis, UnivarInterpolation};
+    use super::univariate::{interpolate, interpolate_at_points, UnivarBasis, UnivarInterpolation};
     use ark_ff::{Fp64, MontBackend, MontConfig};
+    use ark_poly::Polynomial;
 
     #[derive(MontConfig)]
     #[modulus = "11"]
@@ -135,4 +203,29 @@ mod tests {
         assert_eq!(interpolation_b.interpolate(4), Fq11::from(9));
         assert_eq!(interpolation_b.interpolate(5), Fq11::from(8));
     }
+
+    #[test]
+    fn interpolate_matches_univar_interpolation() {
+        let a: [Fq11; 4] = [Fq11::from(3), Fq11::from(1), Fq11::from(4), Fq11::from(1)];
+        let poly = interpolate(&a);
+        let interpolation = UnivarInterpolation::new(a);
+        assert!(poly.coeffs.len() <= 4);
+        for x in 0..11 {
+            assert_eq!(
+                poly.evaluate(&Fq11::from(x as u64)),
+                interpolation.interpolate(x)
+            );
+        }
+    }
+
+    #[test]
+    fn interpolate_at_arbitrary_points() {
+        let points = [Fq11::from(2), Fq11::from(7), Fq11::from(5)];
+        let evals = [Fq11::from(1), Fq11::from(0), Fq11::from(9)];
+        let poly = interpolate_at_points(&points, &evals);
+        assert!(poly.coeffs.len() <= 3);
+        for (x, y) in points.iter().zip(&evals) {
+            assert_eq!(poly.evaluate(x), *y);
+        }
+    }
 }
@@ -1,2 +1,16 @@
+// `#[derive(MontConfig)]` from ark-ff 0.4 puts its impls inside a function
+#![allow(non_local_definitions)]
+
+pub mod circuit;
+pub mod fields;
+pub mod fri;
+pub mod gkr;
 pub mod lagrange;
+pub mod matmult;
+pub mod merkle;
+pub mod pcs;
+pub mod sharp_sat;
 pub mod sumcheck;
+pub mod triangle_count;
+pub mod univariate_sumcheck;
+pub mod zerocheck;
@@ -0,0 +1,251 @@
+use ark_ff::Field;
+use ark_std::rand::RngCore;
+
+use crate::lagrange::multivariate::multivariate::DenseMLE;
+use crate::sumcheck::{FnOracle, MleProduct, Step, Sumcheck, SumcheckError, Transcript};
+
+/// A dense matrix stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<F: Field> {
+    rows: usize,
+    cols: usize,
+    entries: Vec<F>,
+}
+
+impl<F: Field> Matrix<F> {
+    pub fn new(rows: usize, cols: usize, entries: Vec<F>) -> Self {
+        assert_eq!(
+            entries.len(),
+            rows * cols,
+            "matrix should have rows * cols entries"
+        );
+        Matrix {
+            rows,
+            cols,
+            entries,
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<F>>) -> Self {
+        let cols = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == cols),
+            "all rows should have the same length"
+        );
+        Matrix::new(rows.len(), cols, rows.concat())
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> F {
+        self.entries[i * self.cols + j]
+    }
+
+    pub fn transpose(&self) -> Self {
+        let entries = (0..self.cols)
+            .flat_map(|j| (0..self.rows).map(move |i| (i, j)))
+            .map(|(i, j)| self.get(i, j))
+            .collect();
+        Matrix::new(self.cols, self.rows, entries)
+    }
+
+    /// The schoolbook product, `O(n^3)`.
+    pub fn mul(&self, other: &Matrix<F>) -> Matrix<F> {
+        assert_eq!(self.cols, other.rows, "inner dimensions should match");
+        let entries = (0..self.rows)
+            .flat_map(|i| (0..other.cols).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                (0..self.cols)
+                    .map(|k| self.get(i, k) * other.get(k, j))
+                    .sum()
+            })
+            .collect();
+        Matrix::new(self.rows, other.cols, entries)
+    }
+
+    pub fn mul_vec(&self, x: &[F]) -> Vec<F> {
+        assert_eq!(
+            self.cols,
+            x.len(),
+            "vector should have one entry per column"
+        );
+        (0..self.rows)
+            .map(|i| (0..self.cols).map(|j| self.get(i, j) * x[j]).sum())
+            .collect()
+    }
+
+    /// The number of variables for the row and column indices, with both dimensions padded to a
+    /// power of two.
+    pub fn num_vars(&self) -> (usize, usize) {
+        let bits = |n: usize| n.max(1).next_power_of_two().trailing_zeros() as usize;
+        (bits(self.rows), bits(self.cols))
+    }
+
+    /// The MLE of the zero-padded matrix, with the row index as the leading variables:
+    /// `M(i, j)` is at position `(i << col_vars) | j` of the table.
+    pub fn mle(&self) -> DenseMLE<F> {
+        let (row_vars, col_vars) = self.num_vars();
+        let mut evaluations = vec![F::zero(); 1 << (row_vars + col_vars)];
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                evaluations[(i << col_vars) | j] = self.get(i, j);
+            }
+        }
+        DenseMLE::new(row_vars + col_vars, evaluations)
+    }
+}
+
+// f(r, x) as an MLE in x, by fixing the leading variables
+fn fix_leading<F: Field>(f: &DenseMLE<F>, r: &[F]) -> DenseMLE<F> {
+    r.iter()
+        .fold(f.clone(), |f, r_i| f.fix_first_variable(*r_i))
+}
+
+/// Check `C = A * B` with Thaler's MatMult protocol.
+///
+/// The verifier picks random `r_1` and `r_2` for the row and column variables and computes `C(r_1, r_2)`
+/// from `C` in `O(n^2)`. Since the MLE of `A * B` is `sum_k A(r_1, k) * B(k, r_2)`, a single sumcheck
+/// over the `log n` variables of `k` reduces the claim to `A(r_1, r_k) * B(r_k, r_2)`, which the
+/// verifier again evaluates from `A` and `B` itself. The prover's tables are `A(r_1, .)` and `B(., r_2)`.
+pub fn verify_matmult<F: Field>(
+    a: &Matrix<F>,
+    b: &Matrix<F>,
+    c: &Matrix<F>,
+    rng: &mut dyn RngCore,
+) -> Result<Transcript<F>, SumcheckError> {
+    assert_eq!(a.cols, b.rows, "inner dimensions should match");
+    assert_eq!(
+        (c.rows, c.cols),
+        (a.rows, b.cols),
+        "C should be rows(A) x cols(B)"
+    );
+
+    let (row_vars, _) = a.num_vars();
+    let (_, col_vars) = b.num_vars();
+    let r1: Vec<F> = (0..row_vars).map(|_| F::rand(rng)).collect();
+    let r2: Vec<F> = (0..col_vars).map(|_| F::rand(rng)).collect();
+    let claim = c.mle().evaluate(&[r1.as_slice(), &r2].concat());
+
+    // B(k, r_2) is the transpose with its leading variables fixed
+    let a_r1 = fix_leading(&a.mle(), &r1);
+    let b_r2 = fix_leading(&b.transpose().mle(), &r2);
+    let (a_mle, b_mle) = (a.mle(), b.mle());
+    let oracle = FnOracle(move |k: &[F]| {
+        Ok(a_mle.evaluate(&[r1.as_slice(), k].concat())
+            * b_mle.evaluate(&[k, r2.as_slice()].concat()))
+    });
+
+    let mut step = Sumcheck::start(MleProduct::new(vec![a_r1, b_r2]), oracle, claim)?;
+    loop {
+        match step {
+            Step::Round(sumcheck) => step = sumcheck.next_round(rng)?,
+            Step::FinalCheck(sumcheck) => return sumcheck.finish(),
+        }
+    }
+}
+
+/// Freivalds' check: `A (B x) = C x` for a random `x`, in `O(n^2)` without any help from the prover.
+/// A wrong `C` passes with probability at most `1 / |F|`.
+pub fn freivalds<F: Field>(
+    a: &Matrix<F>,
+    b: &Matrix<F>,
+    c: &Matrix<F>,
+    rng: &mut dyn RngCore,
+) -> bool {
+    assert_eq!(a.cols, b.rows, "inner dimensions should match");
+    assert_eq!(
+        (c.rows, c.cols),
+        (a.rows, b.cols),
+        "C should be rows(A) x cols(B)"
+    );
+    let x: Vec<F> = (0..b.cols).map(|_| F::rand(rng)).collect();
+    a.mul_vec(&b.mul_vec(&x)) == c.mul_vec(&x)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::fields::goldilocks::Goldilocks;
+
+    fn random_matrix(rows: usize, cols: usize, rng: &mut dyn RngCore) -> Matrix<Goldilocks> {
+        Matrix::new(
+            rows,
+            cols,
+            (0..rows * cols).map(|_| Goldilocks::rand(rng)).collect(),
+        )
+    }
+
+    #[test]
+    fn mle_agrees_with_entries() {
+        let m = Matrix::from_rows(vec![
+            vec![
+                Goldilocks::from(1u64),
+                Goldilocks::from(2u64),
+                Goldilocks::from(3u64),
+            ],
+            vec![
+                Goldilocks::from(4u64),
+                Goldilocks::from(5u64),
+                Goldilocks::from(6u64),
+            ],
+        ]);
+        assert_eq!(m.num_vars(), (1, 2));
+        let bit = |b: usize| Goldilocks::from(b as u64);
+        let mle = m.mle();
+        for i in 0..2 {
+            for j in 0..3 {
+                assert_eq!(
+                    mle.evaluate(&[bit(i), bit(j >> 1), bit(j & 1)]),
+                    m.get(i, j)
+                );
+            }
+        }
+        // the padding column
+        assert_eq!(
+            mle.evaluate(&[bit(1), bit(1), bit(1)]),
+            Goldilocks::from(0u64)
+        );
+        assert_eq!(m.transpose().transpose(), m);
+    }
+
+    #[test]
+    fn matmult_accepts_product() {
+        let rng = &mut test_rng();
+        let (a, b) = (random_matrix(8, 8, rng), random_matrix(8, 8, rng));
+        let c = a.mul(&b);
+        let transcript = verify_matmult(&a, &b, &c, rng).unwrap();
+        assert_eq!(transcript.round_polynomials.len(), 3);
+        assert!(freivalds(&a, &b, &c, rng));
+    }
+
+    #[test]
+    fn matmult_pads_rectangular_matrices() {
+        let rng = &mut test_rng();
+        let (a, b) = (random_matrix(3, 5, rng), random_matrix(5, 2, rng));
+        let c = a.mul(&b);
+        let transcript = verify_matmult(&a, &b, &c, rng).unwrap();
+        assert_eq!(transcript.round_polynomials.len(), 3);
+    }
+
+    #[test]
+    fn matmult_rejects_wrong_product() {
+        let rng = &mut test_rng();
+        let (a, b) = (random_matrix(4, 4, rng), random_matrix(4, 4, rng));
+        let mut c = a.mul(&b);
+        c.entries[5] += Goldilocks::from(1u64);
+        assert_eq!(
+            verify_matmult(&a, &b, &c, rng),
+            Err(SumcheckError::ClaimMismatch { round: 0 })
+        );
+        assert!(!freivalds(&a, &b, &c, rng));
+    }
+}
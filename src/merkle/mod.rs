@@ -0,0 +1,406 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha2::digest::Digest;
+
+/// The hash a Merkle tree is built with: leaves are hashed from their field elements, inner nodes
+/// by compressing their two children.
+pub trait MerkleHasher<F: Field> {
+    type Digest: Clone + fmt::Debug + PartialEq + Eq + CanonicalSerialize + CanonicalDeserialize;
+
+    fn hash_leaf(leaf: &[F]) -> Self::Digest;
+
+    fn compress(left: &Self::Digest, right: &Self::Digest) -> Self::Digest;
+}
+
+/// A `MerkleHasher` from any `Digest`. Leaves and inner nodes are prefixed with a different byte,
+/// so a leaf can't be passed off as an inner node.
+#[derive(Debug, Clone, Copy)]
+pub struct DigestHasher<D>(PhantomData<D>);
+
+pub type Sha256Hasher = DigestHasher<sha2::Sha256>;
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+impl<F: Field, D: Digest> MerkleHasher<F> for DigestHasher<D> {
+    type Digest = Vec<u8>;
+
+    fn hash_leaf(leaf: &[F]) -> Vec<u8> {
+        let mut bytes = vec![LEAF_PREFIX];
+        for element in leaf {
+            element
+                .serialize_compressed(&mut bytes)
+                .expect("serializing into a vector should not fail");
+        }
+        D::digest(&bytes).to_vec()
+    }
+
+    fn compress(left: &Vec<u8>, right: &Vec<u8>) -> Vec<u8> {
+        let mut hasher = D::new();
+        hasher.update([NODE_PREFIX]);
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().to_vec()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MerkleError {
+    /// An opened index is past the last leaf.
+    IndexOutOfRange { index: usize, num_leaves: usize },
+    /// The proof has the wrong number of leaves or siblings, or its indices aren't strictly increasing.
+    MalformedProof,
+    /// The leaves and siblings hash to a different root.
+    RootMismatch,
+}
+
+impl fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MerkleError::IndexOutOfRange { index, num_leaves } => write!(
+                f,
+                "index {} is out of range for {} leaves",
+                index, num_leaves
+            ),
+            MerkleError::MalformedProof => write!(f, "malformed proof"),
+            MerkleError::RootMismatch => write!(f, "proof does not match the root"),
+        }
+    }
+}
+
+impl std::error::Error for MerkleError {}
+
+/// A Merkle tree whose leaves are vectors of field elements, e.g. the rows of a matrix.
+/// The leaves are padded with empty ones up to a power of two.
+#[derive(Debug, Clone)]
+pub struct MerkleTree<F: Field, H: MerkleHasher<F>> {
+    leaves: Vec<Vec<F>>,
+    // layers[0] are the leaf hashes and the last layer is the root
+    layers: Vec<Vec<H::Digest>>,
+}
+
+impl<F: Field, H: MerkleHasher<F>> MerkleTree<F, H> {
+    pub fn new(leaves: Vec<Vec<F>>) -> Self {
+        assert!(!leaves.is_empty(), "there should be a leaf to commit to");
+        let mut layer: Vec<H::Digest> = leaves.iter().map(|leaf| H::hash_leaf(leaf)).collect();
+        layer.resize(leaves.len().next_power_of_two(), H::hash_leaf(&[]));
+        let mut layers = vec![layer];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| H::compress(&pair[0], &pair[1]))
+                .collect();
+            layers.push(next);
+        }
+        MerkleTree { leaves, layers }
+    }
+
+    /// Commit to each value as its own leaf, e.g. the symbols of a codeword.
+    pub fn from_values(values: Vec<F>) -> Self {
+        MerkleTree::new(values.into_iter().map(|value| vec![value]).collect())
+    }
+
+    pub fn root(&self) -> &H::Digest {
+        &self.layers.last().unwrap()[0]
+    }
+
+    pub fn num_leaves(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn leaf(&self, index: usize) -> &[F] {
+        &self.leaves[index]
+    }
+
+    /// The leaf at `index` with the sibling of every node on its path to the root.
+    pub fn open(&self, index: usize) -> MerklePath<F, H::Digest> {
+        assert!(index < self.num_leaves(), "index out of range");
+        let depth = self.layers.len() - 1;
+        let siblings = (0..depth)
+            .map(|level| self.layers[level][(index >> level) ^ 1].clone())
+            .collect();
+        MerklePath {
+            index,
+            leaf: self.leaves[index].clone(),
+            siblings,
+        }
+    }
+
+    /// Open several leaves at once. A sibling is only included when it can't be computed from the
+    /// other opened leaves, so paths that meet share the nodes above where they join.
+    pub fn open_many(&self, indices: &[usize]) -> MultiProof<F, H::Digest> {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        assert!(!indices.is_empty(), "there should be a leaf to open");
+        assert!(
+            indices.iter().all(|&index| index < self.num_leaves()),
+            "index out of range"
+        );
+
+        let mut siblings = Vec::new();
+        let mut known = indices.clone();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let mut parents = Vec::with_capacity(known.len());
+            let mut i = 0;
+            while i < known.len() {
+                let node = known[i];
+                if node & 1 == 0 && known.get(i + 1) == Some(&(node + 1)) {
+                    i += 2;
+                } else {
+                    siblings.push(layer[node ^ 1].clone());
+                    i += 1;
+                }
+                parents.push(node / 2);
+            }
+            known = parents;
+        }
+
+        MultiProof {
+            leaves: indices.iter().map(|&i| self.leaves[i].clone()).collect(),
+            indices,
+            siblings,
+        }
+    }
+}
+
+// number of levels between the leaves and the root
+fn depth(num_leaves: usize) -> usize {
+    num_leaves.next_power_of_two().trailing_zeros() as usize
+}
+
+fn check_index(index: usize, num_leaves: usize) -> Result<(), MerkleError> {
+    if index >= num_leaves {
+        return Err(MerkleError::IndexOutOfRange { index, num_leaves });
+    }
+    Ok(())
+}
+
+/// An opening of a single leaf.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MerklePath<F: Field, D: CanonicalSerialize + CanonicalDeserialize> {
+    pub index: usize,
+    pub leaf: Vec<F>,
+    /// From the leaf's sibling up to the root's children.
+    pub siblings: Vec<D>,
+}
+
+impl<F: Field, D: CanonicalSerialize + CanonicalDeserialize + Clone + Eq> MerklePath<F, D> {
+    /// Check the leaf against the root of a tree with `num_leaves` leaves.
+    pub fn verify<H: MerkleHasher<F, Digest = D>>(
+        &self,
+        root: &D,
+        num_leaves: usize,
+    ) -> Result<(), MerkleError> {
+        check_index(self.index, num_leaves)?;
+        if self.siblings.len() != depth(num_leaves) {
+            return Err(MerkleError::MalformedProof);
+        }
+        let mut node = H::hash_leaf(&self.leaf);
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            node = if (self.index >> level) & 1 == 0 {
+                H::compress(&node, sibling)
+            } else {
+                H::compress(sibling, &node)
+            };
+        }
+        if &node != root {
+            return Err(MerkleError::RootMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// An opening of several leaves with the siblings shared between their paths, bottom up and left to
+/// right within each level.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultiProof<F: Field, D: CanonicalSerialize + CanonicalDeserialize> {
+    /// Strictly increasing.
+    pub indices: Vec<usize>,
+    pub leaves: Vec<Vec<F>>,
+    pub siblings: Vec<D>,
+}
+
+impl<F: Field, D: CanonicalSerialize + CanonicalDeserialize + Clone + Eq> MultiProof<F, D> {
+    /// Check every leaf against the root of a tree with `num_leaves` leaves, recomputing the nodes
+    /// in the same order `open_many` left them out.
+    pub fn verify<H: MerkleHasher<F, Digest = D>>(
+        &self,
+        root: &D,
+        num_leaves: usize,
+    ) -> Result<(), MerkleError> {
+        if self.indices.is_empty()
+            || self.indices.len() != self.leaves.len()
+            || self.indices.windows(2).any(|pair| pair[0] >= pair[1])
+        {
+            return Err(MerkleError::MalformedProof);
+        }
+        for &index in &self.indices {
+            check_index(index, num_leaves)?;
+        }
+
+        let mut siblings = self.siblings.iter();
+        let mut known: Vec<(usize, D)> = self
+            .indices
+            .iter()
+            .zip(&self.leaves)
+            .map(|(&index, leaf)| (index, H::hash_leaf(leaf)))
+            .collect();
+        for _ in 0..depth(num_leaves) {
+            let mut parents = Vec::with_capacity(known.len());
+            let mut i = 0;
+            while i < known.len() {
+                let (node, digest) = &known[i];
+                let parent = match known.get(i + 1) {
+                    Some((next, right)) if node & 1 == 0 && *next == node + 1 => {
+                        i += 2;
+                        H::compress(digest, right)
+                    }
+                    _ => {
+                        i += 1;
+                        let sibling = siblings.next().ok_or(MerkleError::MalformedProof)?;
+                        if node & 1 == 0 {
+                            H::compress(digest, sibling)
+                        } else {
+                            H::compress(sibling, digest)
+                        }
+                    }
+                };
+                parents.push((node / 2, parent));
+            }
+            known = parents;
+        }
+
+        if siblings.next().is_some() {
+            return Err(MerkleError::MalformedProof);
+        }
+        if &known[0].1 != root {
+            return Err(MerkleError::RootMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::fields::goldilocks::Goldilocks;
+    use crate::lagrange::univariate::univariate::UnivarInterpolation;
+
+    type Tree = MerkleTree<Goldilocks, Sha256Hasher>;
+
+    fn random_rows(rows: usize, cols: usize) -> Vec<Vec<Goldilocks>> {
+        let rng = &mut test_rng();
+        (0..rows)
+            .map(|_| (0..cols).map(|_| Goldilocks::rand(rng)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn single_openings_verify() {
+        let tree = Tree::new(random_rows(6, 3));
+        for index in 0..6 {
+            let path = tree.open(index);
+            assert_eq!(path.leaf, tree.leaf(index));
+            assert_eq!(path.siblings.len(), 3);
+            assert_eq!(path.verify::<Sha256Hasher>(tree.root(), 6), Ok(()));
+        }
+
+        let mut path = tree.open(2);
+        path.leaf[1] += Goldilocks::from(1u64);
+        assert_eq!(
+            path.verify::<Sha256Hasher>(tree.root(), 6),
+            Err(MerkleError::RootMismatch)
+        );
+        // the padding leaves can't be opened
+        path.index = 7;
+        assert_eq!(
+            path.verify::<Sha256Hasher>(tree.root(), 6),
+            Err(MerkleError::IndexOutOfRange {
+                index: 7,
+                num_leaves: 6
+            })
+        );
+    }
+
+    #[test]
+    fn multi_proofs_share_siblings() {
+        let tree = Tree::new(random_rows(16, 2));
+
+        // adjacent leaves need no sibling on the first level
+        let proof = tree.open_many(&[5, 4]);
+        assert_eq!(proof.indices, vec![4, 5]);
+        assert_eq!(proof.siblings.len(), 3);
+        assert_eq!(proof.verify::<Sha256Hasher>(tree.root(), 16), Ok(()));
+
+        let proof = tree.open_many(&[0, 3, 9, 15, 3]);
+        assert!(proof.siblings.len() < 4 * tree.open(0).siblings.len());
+        assert_eq!(proof.verify::<Sha256Hasher>(tree.root(), 16), Ok(()));
+
+        let everything: Vec<usize> = (0..16).collect();
+        assert!(tree.open_many(&everything).siblings.is_empty());
+
+        let mut tampered = proof.clone();
+        tampered.leaves[2][0] += Goldilocks::from(1u64);
+        assert_eq!(
+            tampered.verify::<Sha256Hasher>(tree.root(), 16),
+            Err(MerkleError::RootMismatch)
+        );
+        let mut tampered = proof.clone();
+        tampered.siblings.pop();
+        assert_eq!(
+            tampered.verify::<Sha256Hasher>(tree.root(), 16),
+            Err(MerkleError::MalformedProof)
+        );
+        let mut tampered = proof;
+        tampered.indices.swap(0, 1);
+        assert_eq!(
+            tampered.verify::<Sha256Hasher>(tree.root(), 16),
+            Err(MerkleError::MalformedProof)
+        );
+    }
+
+    #[test]
+    fn proofs_roundtrip_through_serialization() {
+        let tree = Tree::new(random_rows(8, 4));
+        let proof = tree.open_many(&[1, 2, 6]);
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded =
+            MultiProof::<Goldilocks, Vec<u8>>::deserialize_compressed(bytes.as_slice()).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(decoded.verify::<Sha256Hasher>(tree.root(), 8), Ok(()));
+
+        let path = tree.open(3);
+        let mut bytes = Vec::new();
+        path.serialize_compressed(&mut bytes).unwrap();
+        let decoded =
+            MerklePath::<Goldilocks, Vec<u8>>::deserialize_compressed(bytes.as_slice()).unwrap();
+        assert_eq!(decoded, path);
+    }
+
+    #[test]
+    fn commits_to_reed_solomon_codeword() {
+        // rate 1/4 encoding of a degree < 4 polynomial
+        let message = [3u64, 1, 4, 1].map(Goldilocks::from);
+        let interpolation = UnivarInterpolation::new(message);
+        let codeword: Vec<Goldilocks> = (0..16).map(|x| interpolation.interpolate(x)).collect();
+        assert_eq!(codeword[..4], message);
+
+        let tree = Tree::from_values(codeword.clone());
+        let proof = tree.open_many(&[2, 7, 11]);
+        assert_eq!(
+            proof.leaves,
+            vec![vec![codeword[2]], vec![codeword[7]], vec![codeword[11]]]
+        );
+        assert_eq!(proof.verify::<Sha256Hasher>(tree.root(), 16), Ok(()));
+    }
+}
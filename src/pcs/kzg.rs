@@ -0,0 +1,377 @@
+use std::fmt;
+
+use ark_ec::{pairing::Pairing, CurveGroup, Group, VariableBaseMSM};
+use ark_ff::{Field, One, UniformRand, Zero};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::rand::{rngs::StdRng, RngCore, SeedableRng};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KzgError {
+    /// A batch has a different number of commitments and claimed values.
+    WrongNumberOfValues { expected: usize, got: usize },
+    /// `e(C - v * G + z * W, H) != e(W, tau * H)`
+    PairingCheckFailed,
+}
+
+impl fmt::Display for KzgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KzgError::WrongNumberOfValues { expected, got } => {
+                write!(f, "expected {} values, got {}", expected, got)
+            }
+            KzgError::PairingCheckFailed => write!(f, "pairing check failed"),
+        }
+    }
+}
+
+impl std::error::Error for KzgError {}
+
+/// `[p(tau)]_1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KzgCommitment<E: Pairing>(pub E::G1Affine);
+
+/// `[q(tau)]_1` for the quotient `q(X) = (p(X) - p(z)) / (X - z)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KzgProof<E: Pairing>(pub E::G1Affine);
+
+/// The claim `p(point) = value` about the polynomial behind `commitment`, with its proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KzgOpening<E: Pairing> {
+    pub commitment: KzgCommitment<E>,
+    pub point: E::ScalarField,
+    pub value: E::ScalarField,
+    pub proof: KzgProof<E>,
+}
+
+/// Divide `p` by `X - z` with synthetic division, returning the quotient and the remainder `p(z)`.
+fn divide_by_linear<F: Field>(p: &DensePolynomial<F>, z: F) -> (DensePolynomial<F>, F) {
+    let mut quotient = vec![F::zero(); p.coeffs.len().saturating_sub(1)];
+    let mut remainder = F::zero();
+    for (i, &c) in p.coeffs.iter().enumerate().rev() {
+        remainder = remainder * z + c;
+        if i > 0 {
+            quotient[i - 1] = remainder;
+        }
+    }
+    (DensePolynomial::from_coefficients_vec(quotient), remainder)
+}
+
+// sum_i gamma^i * items_i
+fn powers_combination<T, F: Field>(items: impl Iterator<Item = T>, gamma: F) -> T
+where
+    T: Zero + std::ops::Mul<F, Output = T>,
+{
+    let mut power = F::one();
+    let mut acc = T::zero();
+    for item in items {
+        acc = acc + item * power;
+        power *= gamma;
+    }
+    acc
+}
+
+/// The structured reference string `[tau^i]_1` for `i <= max_degree`, with `[1]_2` and `[tau]_2`.
+///
+/// `tau` is drawn from the given rng and dropped once the powers are computed. That makes the setup
+/// trusted: anyone who can reproduce the rng knows `tau` and can open commitments to anything, so
+/// the seeded setup is for experiments only.
+#[derive(Debug, Clone)]
+pub struct KzgSetup<E: Pairing> {
+    powers_of_g: Vec<E::G1Affine>,
+    g2: E::G2Affine,
+    tau_g2: E::G2Affine,
+}
+
+impl<E: Pairing> KzgSetup<E> {
+    pub fn generate(max_degree: usize, rng: &mut dyn RngCore) -> Self {
+        let tau = E::ScalarField::rand(rng);
+        let g = E::G1::generator();
+        let h = E::G2::generator();
+        let mut powers = Vec::with_capacity(max_degree + 1);
+        let mut power = E::ScalarField::one();
+        for _ in 0..=max_degree {
+            powers.push(g * power);
+            power *= tau;
+        }
+        KzgSetup {
+            powers_of_g: E::G1::normalize_batch(&powers),
+            g2: h.into_affine(),
+            tau_g2: (h * tau).into_affine(),
+        }
+    }
+
+    /// `generate` with a `StdRng` seeded from `seed`, so the same setup can be rebuilt anywhere.
+    pub fn from_seed(max_degree: usize, seed: u64) -> Self {
+        Self::generate(max_degree, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn max_degree(&self) -> usize {
+        self.powers_of_g.len() - 1
+    }
+
+    pub fn commit(&self, p: &DensePolynomial<E::ScalarField>) -> KzgCommitment<E> {
+        assert!(
+            p.coeffs.len() <= self.powers_of_g.len(),
+            "polynomial degree exceeds the setup"
+        );
+        KzgCommitment(
+            E::G1::msm_unchecked(&self.powers_of_g[..p.coeffs.len()], &p.coeffs).into_affine(),
+        )
+    }
+
+    /// `[L_i(tau)]_1` for the Lagrange basis over the nodes `0, ..., n - 1`, the same nodes
+    /// `lagrange::univariate::interpolate` uses. Each `L_i = Z / (X - i) / Z'(i)` with
+    /// `Z = prod_j (X - j)` is committed from the powers of `tau`, in O(n^2) overall.
+    pub fn lagrange_basis(&self, n: usize) -> LagrangeBasis<E> {
+        assert!(
+            n > 0 && n <= self.powers_of_g.len(),
+            "basis exceeds the setup"
+        );
+        let vanishing = (0..n as u64).fold(
+            DensePolynomial::from_coefficients_vec(vec![E::ScalarField::one()]),
+            |z, j| {
+                &z * &DensePolynomial::from_coefficients_vec(vec![
+                    -E::ScalarField::from(j),
+                    E::ScalarField::one(),
+                ])
+            },
+        );
+        let basis = (0..n as u64)
+            .map(|i| {
+                let node = E::ScalarField::from(i);
+                let (l, _) = divide_by_linear(&vanishing, node);
+                let weight = l.evaluate(&node).inverse().unwrap();
+                self.commit(&l).0 * weight
+            })
+            .collect::<Vec<_>>();
+        LagrangeBasis {
+            points: E::G1::normalize_batch(&basis),
+        }
+    }
+
+    /// The value `p(z)` and the commitment to the quotient `(p(X) - p(z)) / (X - z)`.
+    pub fn open(
+        &self,
+        p: &DensePolynomial<E::ScalarField>,
+        z: E::ScalarField,
+    ) -> (E::ScalarField, KzgProof<E>) {
+        let (quotient, value) = divide_by_linear(p, z);
+        (value, KzgProof(self.commit(&quotient).0))
+    }
+
+    /// Check `e(C - v * G + z * W, H) = e(W, tau * H)`, i.e. `p(tau) - v = q(tau) * (tau - z)`.
+    pub fn verify(
+        &self,
+        commitment: &KzgCommitment<E>,
+        z: E::ScalarField,
+        value: E::ScalarField,
+        proof: &KzgProof<E>,
+    ) -> Result<(), KzgError> {
+        let lhs = E::G1::from(commitment.0) - E::G1::generator() * value + proof.0 * z;
+        if E::pairing(lhs, self.g2) != E::pairing(proof.0, self.tau_g2) {
+            return Err(KzgError::PairingCheckFailed);
+        }
+        Ok(())
+    }
+
+    /// Open several polynomials at the same point with a single proof for `sum_i gamma^i * p_i`,
+    /// where `gamma` is the verifier's challenge.
+    pub fn open_batch(
+        &self,
+        polynomials: &[DensePolynomial<E::ScalarField>],
+        z: E::ScalarField,
+        gamma: E::ScalarField,
+    ) -> (Vec<E::ScalarField>, KzgProof<E>) {
+        let values = polynomials.iter().map(|p| p.evaluate(&z)).collect();
+        let combined = polynomials
+            .iter()
+            .rev()
+            .fold(DensePolynomial::zero(), |acc, p| &(&acc * gamma) + p);
+        let (_, proof) = self.open(&combined, z);
+        (values, proof)
+    }
+
+    pub fn verify_batch(
+        &self,
+        commitments: &[KzgCommitment<E>],
+        z: E::ScalarField,
+        values: &[E::ScalarField],
+        proof: &KzgProof<E>,
+        gamma: E::ScalarField,
+    ) -> Result<(), KzgError> {
+        if values.len() != commitments.len() {
+            return Err(KzgError::WrongNumberOfValues {
+                expected: commitments.len(),
+                got: values.len(),
+            });
+        }
+        let commitment = powers_combination(commitments.iter().map(|c| E::G1::from(c.0)), gamma);
+        let value = powers_combination(values.iter().copied(), gamma);
+        self.verify(&KzgCommitment(commitment.into_affine()), z, value, proof)
+    }
+
+    /// Check openings of any polynomials at any points with two pairings, by testing a random
+    /// combination of the individual checks:
+    /// `e(sum_i r_i * (C_i - v_i * G + z_i * W_i), H) = e(sum_i r_i * W_i, tau * H)`.
+    pub fn verify_many(
+        &self,
+        openings: &[KzgOpening<E>],
+        rng: &mut dyn RngCore,
+    ) -> Result<(), KzgError> {
+        let mut lhs = E::G1::zero();
+        let mut proofs = E::G1::zero();
+        for opening in openings {
+            let r = E::ScalarField::rand(rng);
+            lhs += (E::G1::from(opening.commitment.0) - E::G1::generator() * opening.value
+                + opening.proof.0 * opening.point)
+                * r;
+            proofs += opening.proof.0 * r;
+        }
+        if E::pairing(lhs, self.g2) != E::pairing(proofs, self.tau_g2) {
+            return Err(KzgError::PairingCheckFailed);
+        }
+        Ok(())
+    }
+}
+
+/// Commitments to the Lagrange basis polynomials over `0, ..., n - 1`, so a vector of evaluations
+/// can be committed without interpolating it first.
+#[derive(Debug, Clone)]
+pub struct LagrangeBasis<E: Pairing> {
+    points: Vec<E::G1Affine>,
+}
+
+impl<E: Pairing> LagrangeBasis<E> {
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The commitment to the polynomial of degree `< n` through `(i, values[i])`, as a single MSM.
+    pub fn commit(&self, values: &[E::ScalarField]) -> KzgCommitment<E> {
+        assert_eq!(
+            values.len(),
+            self.points.len(),
+            "there should be one value per basis polynomial"
+        );
+        KzgCommitment(E::G1::msm_unchecked(&self.points, values).into_affine())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::lagrange::univariate::univariate::{interpolate, UnivarInterpolation};
+
+    type Setup = KzgSetup<Bls12_381>;
+
+    #[test]
+    fn openings_verify() {
+        let rng = &mut test_rng();
+        let setup = Setup::generate(15, rng);
+        let p = DensePolynomial::<Fr>::rand(15, rng);
+        let commitment = setup.commit(&p);
+        let z = Fr::rand(rng);
+        let (value, proof) = setup.open(&p, z);
+        assert_eq!(value, p.evaluate(&z));
+        assert_eq!(setup.verify(&commitment, z, value, &proof), Ok(()));
+        assert_eq!(
+            setup.verify(&commitment, z, value + Fr::one(), &proof),
+            Err(KzgError::PairingCheckFailed)
+        );
+        assert_eq!(
+            setup.verify(&commitment, z + Fr::one(), value, &proof),
+            Err(KzgError::PairingCheckFailed)
+        );
+    }
+
+    #[test]
+    fn seeded_setup_is_reproducible() {
+        let setup = Setup::from_seed(4, 42);
+        assert_eq!(setup.powers_of_g, Setup::from_seed(4, 42).powers_of_g);
+        assert_ne!(setup.powers_of_g, Setup::from_seed(4, 43).powers_of_g);
+        assert_eq!(setup.max_degree(), 4);
+    }
+
+    #[test]
+    fn batched_openings_verify() {
+        let rng = &mut test_rng();
+        let setup = Setup::generate(7, rng);
+        let polynomials: Vec<_> = (0..4)
+            .map(|d| DensePolynomial::<Fr>::rand(2 * d + 1, rng))
+            .collect();
+        let commitments: Vec<_> = polynomials.iter().map(|p| setup.commit(p)).collect();
+        let (z, gamma) = (Fr::rand(rng), Fr::rand(rng));
+        let (mut values, proof) = setup.open_batch(&polynomials, z, gamma);
+        assert_eq!(
+            setup.verify_batch(&commitments, z, &values, &proof, gamma),
+            Ok(())
+        );
+        values[2] += Fr::one();
+        assert_eq!(
+            setup.verify_batch(&commitments, z, &values, &proof, gamma),
+            Err(KzgError::PairingCheckFailed)
+        );
+        assert_eq!(
+            setup.verify_batch(&commitments, z, &values[1..], &proof, gamma),
+            Err(KzgError::WrongNumberOfValues {
+                expected: 4,
+                got: 3
+            })
+        );
+
+        let mut openings: Vec<_> = polynomials
+            .iter()
+            .zip(&commitments)
+            .map(|(p, &commitment)| {
+                let point = Fr::rand(rng);
+                let (value, proof) = setup.open(p, point);
+                KzgOpening {
+                    commitment,
+                    point,
+                    value,
+                    proof,
+                }
+            })
+            .collect();
+        assert_eq!(setup.verify_many(&openings, rng), Ok(()));
+        openings[1].value += Fr::one();
+        assert_eq!(
+            setup.verify_many(&openings, rng),
+            Err(KzgError::PairingCheckFailed)
+        );
+    }
+
+    #[test]
+    fn lagrange_commitments_match_interpolation() {
+        let rng = &mut test_rng();
+        let setup = Setup::generate(7, rng);
+        let values = [2u64, 7, 1, 8, 2, 8].map(Fr::from);
+        let basis = setup.lagrange_basis(values.len());
+        let commitment = basis.commit(&values);
+        let p = interpolate(&values);
+        assert_eq!(commitment, setup.commit(&p));
+
+        // opening at a node gives back the committed value
+        let (value, proof) = setup.open(&p, Fr::from(3u64));
+        assert_eq!(value, values[3]);
+        assert_eq!(
+            setup.verify(&commitment, Fr::from(3u64), value, &proof),
+            Ok(())
+        );
+        // and past the nodes, the extension UnivarInterpolation computes
+        let extension = UnivarInterpolation::new(values).interpolate(9);
+        let (value, proof) = setup.open(&p, Fr::from(9u64));
+        assert_eq!(value, extension);
+        assert_eq!(
+            setup.verify(&commitment, Fr::from(9u64), value, &proof),
+            Ok(())
+        );
+    }
+}
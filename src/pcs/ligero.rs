@@ -0,0 +1,550 @@
+use std::cell::RefCell;
+use std::fmt;
+
+use ark_ff::FftField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{Rng, RngCore};
+
+use crate::lagrange::multivariate::multivariate::DenseMLE;
+use crate::merkle::{MerkleError, MerkleHasher, MerkleTree, MultiProof};
+use crate::sumcheck::oracle::Oracle;
+use crate::sumcheck::{
+    MleProduct, OracleError, PolynomialInfo, Prover, SubClaim, SumcheckError, Transcript, Verifier,
+};
+
+/// The rate and number of column checks of a Ligero commitment. Each row is encoded to `blowup`
+/// times its length. A prover whose committed rows are far from codewords passes each column check
+/// with probability bounded away from 1, so `num_queries` sets the soundness error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LigeroParameters {
+    pub blowup: usize,
+    pub num_queries: usize,
+}
+
+impl LigeroParameters {
+    pub fn new(blowup: usize, num_queries: usize) -> Self {
+        assert!(
+            blowup.is_power_of_two() && blowup > 1,
+            "blowup should be a power of two greater than 1"
+        );
+        assert!(num_queries > 0, "there should be at least one query");
+        LigeroParameters {
+            blowup,
+            num_queries,
+        }
+    }
+}
+
+/// The MLE's table is laid out as a `2^row_vars x 2^col_vars` matrix, with the leading variables
+/// picking the row, like `Matrix::mle`.
+fn split_vars(num_vars: usize) -> (usize, usize) {
+    (num_vars / 2, num_vars - num_vars / 2)
+}
+
+fn encoding_domain<F: FftField>(
+    params: &LigeroParameters,
+    col_vars: usize,
+) -> Radix2EvaluationDomain<F> {
+    Radix2EvaluationDomain::new(params.blowup << col_vars)
+        .expect("field should have a subgroup of the codeword size")
+}
+
+// a row read as the coefficients of a polynomial, evaluated over the encoding domain
+fn encode<F: FftField>(row: &[F], domain: &Radix2EvaluationDomain<F>) -> Vec<F> {
+    domain.fft(row)
+}
+
+// sum_i coefficients[i] * rows[i]
+fn combine<F: FftField>(rows: &[Vec<F>], coefficients: &[F]) -> Vec<F> {
+    let mut combined = vec![F::zero(); rows[0].len()];
+    for (row, c) in rows.iter().zip(coefficients) {
+        for (acc, value) in combined.iter_mut().zip(row) {
+            *acc += *c * value;
+        }
+    }
+    combined
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LigeroCommitment<D: CanonicalSerialize + CanonicalDeserialize> {
+    pub root: D,
+    pub num_vars: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LigeroError {
+    /// The point doesn't have one coordinate per variable of the committed MLE.
+    WrongNumberOfVariables {
+        expected: usize,
+        got: usize,
+    },
+    /// A combined row sent by the prover has the wrong length.
+    WrongRowLength {
+        expected: usize,
+        got: usize,
+    },
+    /// The opened columns aren't the sampled ones, or don't have one entry per row.
+    MalformedOpening,
+    Merkle(MerkleError),
+    /// The random combination of the rows doesn't encode to the same combination of column `column`.
+    ProximityCheckFailed {
+        column: usize,
+    },
+    /// The `eq`-weighted combination of the rows doesn't encode to the same combination of column `column`.
+    EvaluationCheckFailed {
+        column: usize,
+    },
+}
+
+impl fmt::Display for LigeroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LigeroError::WrongNumberOfVariables { expected, got } => write!(
+                f,
+                "expected a point with {} coordinates, got {}",
+                expected, got
+            ),
+            LigeroError::WrongRowLength { expected, got } => {
+                write!(f, "expected a row of length {}, got {}", expected, got)
+            }
+            LigeroError::MalformedOpening => write!(f, "malformed column opening"),
+            LigeroError::Merkle(e) => write!(f, "merkle error: {}", e),
+            LigeroError::ProximityCheckFailed { column } => {
+                write!(f, "proximity check failed at column {}", column)
+            }
+            LigeroError::EvaluationCheckFailed { column } => {
+                write!(f, "evaluation check failed at column {}", column)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LigeroError {}
+
+impl From<MerkleError> for LigeroError {
+    fn from(e: MerkleError) -> Self {
+        LigeroError::Merkle(e)
+    }
+}
+
+/// A Ligero-style commitment to a multilinear polynomial (as in Brakedown, with a Reed-Solomon code).
+///
+/// The table of `f` is arranged as a matrix `T`, so that `f(z) = eq(z_row, .)^T * T * eq(z_col, .)`.
+/// Each row is encoded and the columns of the encoded matrix are committed with a Merkle tree. To open
+/// at `z` the prover sends `u = eq(z_row, .)^T * T`, from which the verifier gets `f(z) = <u, eq(z_col, .)>`.
+/// Since the code is linear, `Enc(u)` is the same combination of the encoded rows, which the verifier
+/// spot checks on a few opened columns, along with a random combination of the rows that shows the
+/// committed rows are close to codewords in the first place.
+pub struct LigeroProver<F: FftField, H: MerkleHasher<F>> {
+    params: LigeroParameters,
+    num_vars: usize,
+    rows: Vec<Vec<F>>,
+    tree: MerkleTree<F, H>,
+}
+
+impl<F: FftField, H: MerkleHasher<F>> LigeroProver<F, H> {
+    pub fn commit(params: LigeroParameters, f: &DenseMLE<F>) -> Self {
+        let (row_vars, col_vars) = split_vars(f.num_vars());
+        let rows: Vec<Vec<F>> = f
+            .evaluations()
+            .chunks(1 << col_vars)
+            .map(<[F]>::to_vec)
+            .collect();
+        debug_assert_eq!(rows.len(), 1 << row_vars);
+        let domain = encoding_domain(&params, col_vars);
+        let encoded: Vec<Vec<F>> = rows.iter().map(|row| encode(row, &domain)).collect();
+        let columns = (0..domain.size())
+            .map(|j| encoded.iter().map(|row| row[j]).collect())
+            .collect();
+        LigeroProver {
+            params,
+            num_vars: f.num_vars(),
+            rows,
+            tree: MerkleTree::new(columns),
+        }
+    }
+
+    pub fn commitment(&self) -> LigeroCommitment<H::Digest> {
+        LigeroCommitment {
+            root: self.tree.root().clone(),
+            num_vars: self.num_vars,
+        }
+    }
+
+    /// The verifier's random combination of the rows, for the proximity check.
+    pub fn combine_rows(&self, coefficients: &[F]) -> Vec<F> {
+        assert_eq!(
+            coefficients.len(),
+            self.rows.len(),
+            "there should be a coefficient per row"
+        );
+        combine(&self.rows, coefficients)
+    }
+
+    /// `u = eq(z_row, .)^T * T` for the point `z`.
+    pub fn evaluation_row(&self, point: &[F]) -> Vec<F> {
+        assert_eq!(point.len(), self.num_vars, "point has the wrong dimension");
+        let (row_vars, _) = split_vars(self.num_vars);
+        combine(
+            &self.rows,
+            DenseMLE::eq_table(&point[..row_vars]).evaluations(),
+        )
+    }
+
+    pub fn open_columns(&self, columns: &[usize]) -> MultiProof<F, H::Digest> {
+        self.tree.open_many(columns)
+    }
+}
+
+pub struct LigeroVerifier<F: FftField, H: MerkleHasher<F>> {
+    params: LigeroParameters,
+    commitment: LigeroCommitment<H::Digest>,
+    point: Vec<F>,
+    challenge: Vec<F>,
+    test_row: Vec<F>,
+    evaluation_row: Vec<F>,
+    columns: Vec<usize>,
+}
+
+impl<F: FftField, H: MerkleHasher<F>> LigeroVerifier<F, H> {
+    /// Start checking an opening of `commitment` at `point`.
+    pub fn init(
+        params: LigeroParameters,
+        commitment: LigeroCommitment<H::Digest>,
+        point: &[F],
+    ) -> Result<Self, LigeroError> {
+        if point.len() != commitment.num_vars {
+            return Err(LigeroError::WrongNumberOfVariables {
+                expected: commitment.num_vars,
+                got: point.len(),
+            });
+        }
+        Ok(LigeroVerifier {
+            params,
+            commitment,
+            point: point.to_vec(),
+            challenge: vec![],
+            test_row: vec![],
+            evaluation_row: vec![],
+            columns: vec![],
+        })
+    }
+
+    /// Random coefficients for the proximity check, one per row.
+    pub fn challenge(&mut self, rng: &mut dyn RngCore) -> Vec<F> {
+        let (row_vars, _) = split_vars(self.commitment.num_vars);
+        self.challenge = (0..1 << row_vars).map(|_| F::rand(rng)).collect();
+        self.challenge.clone()
+    }
+
+    /// Receive the combination of the rows with the challenge and the evaluation row `u`.
+    pub fn receive_rows(
+        &mut self,
+        test_row: Vec<F>,
+        evaluation_row: Vec<F>,
+    ) -> Result<(), LigeroError> {
+        let (_, col_vars) = split_vars(self.commitment.num_vars);
+        for row in [&test_row, &evaluation_row] {
+            if row.len() != 1 << col_vars {
+                return Err(LigeroError::WrongRowLength {
+                    expected: 1 << col_vars,
+                    got: row.len(),
+                });
+            }
+        }
+        self.test_row = test_row;
+        self.evaluation_row = evaluation_row;
+        Ok(())
+    }
+
+    /// Pick the columns of the encoded matrix to spot check.
+    pub fn sample_columns(&mut self, rng: &mut dyn RngCore) -> Vec<usize> {
+        let (_, col_vars) = split_vars(self.commitment.num_vars);
+        let width = self.params.blowup << col_vars;
+        self.columns = (0..self.params.num_queries)
+            .map(|_| rng.gen_range(0..width))
+            .collect();
+        self.columns.clone()
+    }
+
+    /// Check the opened columns against both rows and return `f(z)`.
+    pub fn verify_columns(&self, opening: &MultiProof<F, H::Digest>) -> Result<F, LigeroError> {
+        let (row_vars, col_vars) = split_vars(self.commitment.num_vars);
+        assert!(
+            !self.columns.is_empty() && !self.test_row.is_empty(),
+            "rows should be received and columns sampled first"
+        );
+        let mut expected = self.columns.clone();
+        expected.sort_unstable();
+        expected.dedup();
+        if opening.indices != expected
+            || opening
+                .leaves
+                .iter()
+                .any(|column| column.len() != 1 << row_vars)
+        {
+            return Err(LigeroError::MalformedOpening);
+        }
+        let domain = encoding_domain(&self.params, col_vars);
+        opening.verify::<H>(&self.commitment.root, domain.size())?;
+
+        let encoded_test = encode(&self.test_row, &domain);
+        let encoded_evaluation = encode(&self.evaluation_row, &domain);
+        let eq_row = DenseMLE::eq_table(&self.point[..row_vars]);
+        for (&column, values) in opening.indices.iter().zip(&opening.leaves) {
+            let inner = |coefficients: &[F]| -> F {
+                values.iter().zip(coefficients).map(|(v, c)| *v * c).sum()
+            };
+            if inner(&self.challenge) != encoded_test[column] {
+                return Err(LigeroError::ProximityCheckFailed { column });
+            }
+            if inner(eq_row.evaluations()) != encoded_evaluation[column] {
+                return Err(LigeroError::EvaluationCheckFailed { column });
+            }
+        }
+
+        let eq_col = DenseMLE::eq_table(&self.point[row_vars..]);
+        Ok(self
+            .evaluation_row
+            .iter()
+            .zip(eq_col.evaluations())
+            .map(|(u, e)| *u * e)
+            .sum())
+    }
+}
+
+/// Open `prover`'s commitment at `point` and return the evaluation once the verifier accepts it.
+pub fn open_and_verify<F: FftField, H: MerkleHasher<F>>(
+    prover: &LigeroProver<F, H>,
+    commitment: &LigeroCommitment<H::Digest>,
+    point: &[F],
+    rng: &mut dyn RngCore,
+) -> Result<F, LigeroError> {
+    let mut verifier = LigeroVerifier::<F, H>::init(prover.params, commitment.clone(), point)?;
+    let challenge = verifier.challenge(rng);
+    verifier.receive_rows(
+        prover.combine_rows(&challenge),
+        prover.evaluation_row(point),
+    )?;
+    let columns = verifier.sample_columns(rng);
+    verifier.verify_columns(&prover.open_columns(&columns))
+}
+
+/// The committed MLE as the oracle for the sumcheck verifier's final check: every query is answered
+/// by an opening that the verifier checks against the commitment.
+pub struct LigeroOracle<'a, F: FftField, H: MerkleHasher<F>> {
+    prover: &'a LigeroProver<F, H>,
+    commitment: LigeroCommitment<H::Digest>,
+    rng: RefCell<&'a mut dyn RngCore>,
+}
+
+impl<'a, F: FftField, H: MerkleHasher<F>> LigeroOracle<'a, F, H> {
+    pub fn new(
+        prover: &'a LigeroProver<F, H>,
+        commitment: LigeroCommitment<H::Digest>,
+        rng: &'a mut dyn RngCore,
+    ) -> Self {
+        LigeroOracle {
+            prover,
+            commitment,
+            rng: RefCell::new(rng),
+        }
+    }
+}
+
+impl<'a, F: FftField, H: MerkleHasher<F>> Oracle<F> for LigeroOracle<'a, F, H> {
+    fn evaluate(&self, point: &[F]) -> Result<F, OracleError> {
+        if point.len() != self.commitment.num_vars {
+            return Err(OracleError::InvalidPoint {
+                expected: self.commitment.num_vars,
+                got: point.len(),
+            });
+        }
+        let mut rng = self.rng.borrow_mut();
+        open_and_verify(self.prover, &self.commitment, point, *rng)
+            .map_err(|e| OracleError::Unavailable(e.to_string()))
+    }
+
+    fn num_vars(&self) -> Option<usize> {
+        Some(self.commitment.num_vars)
+    }
+}
+
+/// Sumcheck for `sum_x f(x)` where the verifier only holds a commitment to `f`: the rounds run without
+/// `f`, and the remaining subclaim is checked with an opening at the challenge point.
+pub fn run_committed_sumcheck<F: FftField, H: MerkleHasher<F>>(
+    params: LigeroParameters,
+    f: DenseMLE<F>,
+    rng: &mut dyn RngCore,
+) -> Result<Transcript<F>, SumcheckError> {
+    let committed = LigeroProver::<F, H>::commit(params, &f);
+    let claim = f.evaluations().iter().copied().sum();
+    run_with_commitment(&committed, committed.commitment(), f, claim, rng)
+}
+
+fn run_with_commitment<F: FftField, H: MerkleHasher<F>>(
+    committed: &LigeroProver<F, H>,
+    commitment: LigeroCommitment<H::Digest>,
+    f: DenseMLE<F>,
+    claim: F,
+    rng: &mut dyn RngCore,
+) -> Result<Transcript<F>, SumcheckError> {
+    let info = PolynomialInfo {
+        num_vars: commitment.num_vars,
+        max_degree: 1,
+    };
+    let mut prover = Prover::init(MleProduct::new(vec![f]))?;
+    let mut verifier = Verifier::<F>::init_deferred(claim, info);
+    let mut round_polynomials = vec![];
+    let mut r = None;
+    for _ in 0..info.num_vars {
+        let poly = prover.prove_round(r);
+        round_polynomials.push(poly.clone());
+        r = verifier.verify_round(poly, rng)?;
+    }
+    let subclaim = match verifier.subclaim() {
+        Some(subclaim) => subclaim.clone(),
+        // a constant has no rounds, so the claim itself is checked against the opening at `()`
+        None if info.num_vars == 0 => SubClaim {
+            point: vec![],
+            expected_value: claim,
+        },
+        None => return Err(SumcheckError::ProtocolNotFinished),
+    };
+    if !subclaim.check(&LigeroOracle::new(committed, commitment, rng))? {
+        return Err(SumcheckError::FinalCheckFailed);
+    }
+    Ok(Transcript {
+        claim,
+        round_polynomials,
+        challenges: subclaim.point,
+        final_evaluation: subclaim.expected_value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::fields::goldilocks::Goldilocks;
+    use crate::merkle::Sha256Hasher;
+
+    type Committed = LigeroProver<Goldilocks, Sha256Hasher>;
+
+    fn random_mle(num_vars: usize, rng: &mut dyn RngCore) -> DenseMLE<Goldilocks> {
+        DenseMLE::new(
+            num_vars,
+            (0..1 << num_vars).map(|_| Goldilocks::rand(rng)).collect(),
+        )
+    }
+
+    #[test]
+    fn openings_match_evaluations() {
+        let rng = &mut test_rng();
+        let params = LigeroParameters::new(4, 8);
+        for num_vars in [1, 4, 5] {
+            let f = random_mle(num_vars, rng);
+            let committed = Committed::commit(params, &f);
+            let point: Vec<Goldilocks> = (0..num_vars).map(|_| Goldilocks::rand(rng)).collect();
+            assert_eq!(
+                open_and_verify(&committed, &committed.commitment(), &point, rng),
+                Ok(f.evaluate(&point))
+            );
+        }
+    }
+
+    #[test]
+    fn opening_rejects_a_wrong_evaluation_row() {
+        let rng = &mut test_rng();
+        let params = LigeroParameters::new(2, 8);
+        let f = random_mle(4, rng);
+        let committed = Committed::commit(params, &f);
+        let point: Vec<Goldilocks> = (0..4).map(|_| Goldilocks::rand(rng)).collect();
+
+        let mut verifier =
+            LigeroVerifier::<_, Sha256Hasher>::init(params, committed.commitment(), &point)
+                .unwrap();
+        let challenge = verifier.challenge(rng);
+        let mut evaluation_row = committed.evaluation_row(&point);
+        evaluation_row[0] += Goldilocks::from(1u64);
+        verifier
+            .receive_rows(committed.combine_rows(&challenge), evaluation_row)
+            .unwrap();
+        let columns = verifier.sample_columns(rng);
+        assert!(matches!(
+            verifier.verify_columns(&committed.open_columns(&columns)),
+            Err(LigeroError::EvaluationCheckFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn opening_rejects_another_commitment() {
+        let rng = &mut test_rng();
+        let params = LigeroParameters::new(2, 4);
+        let committed = Committed::commit(params, &random_mle(4, rng));
+        let other = Committed::commit(params, &random_mle(4, rng));
+        let point: Vec<Goldilocks> = (0..4).map(|_| Goldilocks::rand(rng)).collect();
+        assert_eq!(
+            open_and_verify(&committed, &other.commitment(), &point, rng),
+            Err(LigeroError::Merkle(MerkleError::RootMismatch))
+        );
+        assert_eq!(
+            open_and_verify(&committed, &committed.commitment(), &point[1..], rng),
+            Err(LigeroError::WrongNumberOfVariables {
+                expected: 4,
+                got: 3
+            })
+        );
+    }
+
+    #[test]
+    fn committed_sumcheck_is_a_complete_argument() {
+        let rng = &mut test_rng();
+        let params = LigeroParameters::new(4, 8);
+        let f = random_mle(6, rng);
+        let transcript = run_committed_sumcheck::<_, Sha256Hasher>(params, f.clone(), rng).unwrap();
+        assert_eq!(transcript.round_polynomials.len(), 6);
+        assert_eq!(
+            transcript.final_evaluation,
+            f.evaluate(&transcript.challenges)
+        );
+    }
+
+    #[test]
+    fn committed_sumcheck_rejects_prover_switching_polynomials() {
+        let rng = &mut test_rng();
+        let params = LigeroParameters::new(4, 8);
+        let f = random_mle(4, rng);
+        let committed = Committed::commit(params, &f);
+        // run the rounds on a different polynomial with the same sum
+        let mut evaluations = f.evaluations().to_vec();
+        evaluations[0] += Goldilocks::from(1u64);
+        evaluations[1] -= Goldilocks::from(1u64);
+        let g = DenseMLE::new(4, evaluations);
+        let claim = f.evaluations().iter().copied().sum();
+        assert_eq!(
+            run_with_commitment(&committed, committed.commitment(), g, claim, rng),
+            Err(SumcheckError::FinalCheckFailed)
+        );
+    }
+
+    #[test]
+    fn committed_sumcheck_over_a_constant() {
+        let rng = &mut test_rng();
+        let params = LigeroParameters::new(4, 8);
+        let f = random_mle(0, rng);
+        let transcript = run_committed_sumcheck::<_, Sha256Hasher>(params, f.clone(), rng).unwrap();
+        assert!(transcript.round_polynomials.is_empty());
+        assert_eq!(transcript.final_evaluation, f.evaluations()[0]);
+
+        let committed = Committed::commit(params, &f);
+        let claim = f.evaluations()[0] + Goldilocks::from(1u64);
+        assert_eq!(
+            run_with_commitment(&committed, committed.commitment(), f, claim, rng),
+            Err(SumcheckError::FinalCheckFailed)
+        );
+    }
+}
@@ -0,0 +1,9 @@
+pub use kzg::{KzgCommitment, KzgError, KzgOpening, KzgProof, KzgSetup, LagrangeBasis};
+pub use ligero::{
+    open_and_verify, run_committed_sumcheck, LigeroCommitment, LigeroError, LigeroOracle,
+    LigeroParameters, LigeroProver, LigeroVerifier,
+};
+pub use pst::{PstCommitment, PstError, PstOracle, PstProof, PstSetup};
+pub mod kzg;
+pub mod ligero;
+pub mod pst;
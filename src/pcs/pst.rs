@@ -0,0 +1,351 @@
+use std::fmt;
+
+use ark_ec::{pairing::Pairing, CurveGroup, Group, VariableBaseMSM};
+use ark_ff::UniformRand;
+use ark_std::rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::lagrange::multivariate::multivariate::DenseMLE;
+use crate::sumcheck::oracle::Oracle;
+use crate::sumcheck::OracleError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PstError {
+    /// The point or the proof doesn't match the commitment's number of variables, or the setup is too small.
+    WrongNumberOfVariables { expected: usize, got: usize },
+    /// `e(C - v * G, H) != prod_i e(W_i, [tau_i - r_i]_2)`
+    PairingCheckFailed,
+}
+
+impl fmt::Display for PstError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PstError::WrongNumberOfVariables { expected, got } => {
+                write!(f, "expected {} variables, got {}", expected, got)
+            }
+            PstError::PairingCheckFailed => write!(f, "pairing check failed"),
+        }
+    }
+}
+
+impl std::error::Error for PstError {}
+
+/// `[f(tau)]_1` for an MLE `f` over `num_vars` variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PstCommitment<E: Pairing> {
+    pub f_tau: E::G1Affine,
+    pub num_vars: usize,
+}
+
+/// `[q_i(tau_(i+1), ..., tau_v)]_1` for each variable `i`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PstProof<E: Pairing> {
+    pub quotients: Vec<E::G1Affine>,
+}
+
+/// The PST (multilinear KZG) reference string for MLEs over up to `num_vars` variables.
+///
+/// For a secret `tau` in `F^v`, level `k` holds `[eq((tau_(k+1), ..., tau_v), b)]_1` for every `b` in
+/// `{0,1}^(v-k)`, so that committing to an evaluation table is a single MSM. An MLE over `v' < v`
+/// variables is read as a polynomial in the last `v'` coordinates of `tau` and committed with level
+/// `v - v'`. As with `KzgSetup`, whoever can reproduce the rng learns `tau`.
+#[derive(Debug, Clone)]
+pub struct PstSetup<E: Pairing> {
+    eq_powers: Vec<Vec<E::G1Affine>>,
+    g2: E::G2Affine,
+    // [tau_i]_2
+    tau_g2: Vec<E::G2Affine>,
+}
+
+impl<E: Pairing> PstSetup<E> {
+    pub fn generate(num_vars: usize, rng: &mut dyn RngCore) -> Self {
+        let tau: Vec<E::ScalarField> = (0..num_vars).map(|_| E::ScalarField::rand(rng)).collect();
+        let g = E::G1::generator();
+        let h = E::G2::generator();
+        let eq_powers = (0..=num_vars)
+            .map(|k| {
+                let table = DenseMLE::eq_table(&tau[k..]);
+                let points: Vec<E::G1> = table.evaluations().iter().map(|e| g * e).collect();
+                E::G1::normalize_batch(&points)
+            })
+            .collect();
+        let tau_g2: Vec<E::G2> = tau.iter().map(|t| h * t).collect();
+        PstSetup {
+            eq_powers,
+            g2: h.into_affine(),
+            tau_g2: E::G2::normalize_batch(&tau_g2),
+        }
+    }
+
+    /// `generate` with a `StdRng` seeded from `seed`.
+    pub fn from_seed(num_vars: usize, seed: u64) -> Self {
+        Self::generate(num_vars, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.tau_g2.len()
+    }
+
+    // the level whose basis covers the last `num_vars` coordinates of tau
+    fn level(&self, num_vars: usize) -> usize {
+        assert!(
+            num_vars <= self.num_vars(),
+            "MLE has more variables than the setup"
+        );
+        self.num_vars() - num_vars
+    }
+
+    fn commit_table(&self, table: &[E::ScalarField], level: usize) -> E::G1Affine {
+        E::G1::msm_unchecked(&self.eq_powers[level], table).into_affine()
+    }
+
+    pub fn commit(&self, f: &DenseMLE<E::ScalarField>) -> PstCommitment<E> {
+        PstCommitment {
+            f_tau: self.commit_table(f.evaluations(), self.level(f.num_vars())),
+            num_vars: f.num_vars(),
+        }
+    }
+
+    /// `f(r)` and the commitments to the `q_i` in `f(x) - f(r) = sum_i (x_i - r_i) * q_i(x_(i+1), ..., x_v)`.
+    ///
+    /// With `f_i` the table of `f` with `x_1, ..., x_i` fixed to `r_1, ..., r_i`, `q_i` is the
+    /// difference of the two halves of `f_(i-1)`, i.e. `f_(i-1)(1, .) - f_(i-1)(0, .)`.
+    pub fn open(
+        &self,
+        f: &DenseMLE<E::ScalarField>,
+        point: &[E::ScalarField],
+    ) -> (E::ScalarField, PstProof<E>) {
+        assert_eq!(point.len(), f.num_vars(), "point has the wrong dimension");
+        let level = self.level(f.num_vars());
+        let mut table = f.clone();
+        let mut quotients = Vec::with_capacity(point.len());
+        for (i, r_i) in point.iter().enumerate() {
+            let (lo, hi) = table.evaluations().split_at(table.evaluations().len() / 2);
+            let quotient: Vec<E::ScalarField> = hi.iter().zip(lo).map(|(b, a)| *b - a).collect();
+            quotients.push(self.commit_table(&quotient, level + i + 1));
+            table = table.fix_first_variable(*r_i);
+        }
+        (table.evaluations()[0], PstProof { quotients })
+    }
+
+    /// Check `e(C - v * G, H) = prod_i e(W_i, [tau_i]_2 - r_i * H)`, which holds when
+    /// `f(tau) - v = sum_i (tau_i - r_i) * q_i(tau)`.
+    pub fn verify(
+        &self,
+        commitment: &PstCommitment<E>,
+        point: &[E::ScalarField],
+        value: E::ScalarField,
+        proof: &PstProof<E>,
+    ) -> Result<(), PstError> {
+        if commitment.num_vars > self.num_vars() {
+            return Err(PstError::WrongNumberOfVariables {
+                expected: self.num_vars(),
+                got: commitment.num_vars,
+            });
+        }
+        if point.len() != commitment.num_vars {
+            return Err(PstError::WrongNumberOfVariables {
+                expected: commitment.num_vars,
+                got: point.len(),
+            });
+        }
+        if proof.quotients.len() != point.len() {
+            return Err(PstError::WrongNumberOfVariables {
+                expected: point.len(),
+                got: proof.quotients.len(),
+            });
+        }
+        let level = self.level(point.len());
+        let lhs = E::pairing(
+            E::G1::from(commitment.f_tau) - E::G1::generator() * value,
+            self.g2,
+        );
+        let shifted: Vec<E::G2> = self.tau_g2[level..]
+            .iter()
+            .zip(point)
+            .map(|(tau_i, r_i)| E::G2::from(*tau_i) - E::G2::generator() * r_i)
+            .collect();
+        let rhs = E::multi_pairing(&proof.quotients, E::G2::normalize_batch(&shifted));
+        if lhs != rhs {
+            return Err(PstError::PairingCheckFailed);
+        }
+        Ok(())
+    }
+}
+
+/// A committed MLE as the oracle for the sumcheck verifier's final check. The prover's side holds `f`
+/// and answers the query with an opening, which the verifier checks against the commitment before
+/// using the value.
+pub struct PstOracle<'a, E: Pairing> {
+    setup: &'a PstSetup<E>,
+    f: &'a DenseMLE<E::ScalarField>,
+    commitment: PstCommitment<E>,
+}
+
+impl<'a, E: Pairing> PstOracle<'a, E> {
+    pub fn new(
+        setup: &'a PstSetup<E>,
+        f: &'a DenseMLE<E::ScalarField>,
+        commitment: PstCommitment<E>,
+    ) -> Self {
+        assert_eq!(
+            f.num_vars(),
+            commitment.num_vars,
+            "f should be the committed MLE"
+        );
+        PstOracle {
+            setup,
+            f,
+            commitment,
+        }
+    }
+}
+
+impl<'a, E: Pairing> Oracle<E::ScalarField> for PstOracle<'a, E> {
+    fn evaluate(&self, point: &[E::ScalarField]) -> Result<E::ScalarField, OracleError> {
+        if point.len() != self.commitment.num_vars {
+            return Err(OracleError::InvalidPoint {
+                expected: self.commitment.num_vars,
+                got: point.len(),
+            });
+        }
+        let (value, proof) = self.setup.open(self.f, point);
+        self.setup
+            .verify(&self.commitment, point, value, &proof)
+            .map_err(|e| OracleError::Unavailable(e.to_string()))?;
+        Ok(value)
+    }
+
+    fn num_vars(&self) -> Option<usize> {
+        Some(self.commitment.num_vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::One;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::sumcheck::{MleProduct, Step, Sumcheck, SumcheckError};
+
+    type Setup = PstSetup<Bls12_381>;
+
+    fn random_mle(num_vars: usize, rng: &mut dyn RngCore) -> DenseMLE<Fr> {
+        DenseMLE::new(
+            num_vars,
+            (0..1 << num_vars).map(|_| Fr::rand(rng)).collect(),
+        )
+    }
+
+    #[test]
+    fn openings_verify() {
+        let rng = &mut test_rng();
+        let setup = Setup::generate(4, rng);
+        // the smaller MLEs use the trailing coordinates of tau
+        for num_vars in [4, 2, 0] {
+            let f = random_mle(num_vars, rng);
+            let commitment = setup.commit(&f);
+            let point: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(rng)).collect();
+            let (value, proof) = setup.open(&f, &point);
+            assert_eq!(value, f.evaluate(&point));
+            assert_eq!(proof.quotients.len(), num_vars);
+            assert_eq!(setup.verify(&commitment, &point, value, &proof), Ok(()));
+            assert_eq!(
+                setup.verify(&commitment, &point, value + Fr::one(), &proof),
+                Err(PstError::PairingCheckFailed)
+            );
+        }
+    }
+
+    #[test]
+    fn commitment_is_evaluation_at_tau() {
+        // the eq basis makes the commitment [f(tau)]_1, so it is linear in the table
+        let rng = &mut test_rng();
+        let setup = Setup::from_seed(3, 7);
+        let (f, g) = (random_mle(3, rng), random_mle(3, rng));
+        let sum = DenseMLE::new(
+            3,
+            f.evaluations()
+                .iter()
+                .zip(g.evaluations())
+                .map(|(a, b)| *a + b)
+                .collect(),
+        );
+        assert_eq!(
+            setup.commit(&sum).f_tau,
+            (setup.commit(&f).f_tau + setup.commit(&g).f_tau).into_affine()
+        );
+        assert_eq!(setup.commit(&f), Setup::from_seed(3, 7).commit(&f));
+    }
+
+    #[test]
+    fn verify_rejects_mismatched_proofs() {
+        let rng = &mut test_rng();
+        let setup = Setup::generate(3, rng);
+        let f = random_mle(3, rng);
+        let commitment = setup.commit(&f);
+        let point: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let (value, mut proof) = setup.open(&f, &point);
+        proof.quotients.swap(0, 1);
+        assert_eq!(
+            setup.verify(&commitment, &point, value, &proof),
+            Err(PstError::PairingCheckFailed)
+        );
+        proof.quotients.pop();
+        assert_eq!(
+            setup.verify(&commitment, &point, value, &proof),
+            Err(PstError::WrongNumberOfVariables {
+                expected: 3,
+                got: 2
+            })
+        );
+
+        // a valid opening of an MLE over fewer variables doesn't open the commitment
+        let g = random_mle(2, rng);
+        let (value, proof) = setup.open(&g, &point[1..]);
+        assert_eq!(
+            setup.verify(&commitment, &point[1..], value, &proof),
+            Err(PstError::WrongNumberOfVariables {
+                expected: 3,
+                got: 2
+            })
+        );
+    }
+
+    fn run(
+        g: DenseMLE<Fr>,
+        oracle: PstOracle<Bls12_381>,
+        claim: Fr,
+        rng: &mut dyn RngCore,
+    ) -> Result<(), SumcheckError> {
+        let mut step = Sumcheck::start(MleProduct::new(vec![g]), oracle, claim)?;
+        loop {
+            match step {
+                Step::Round(sumcheck) => step = sumcheck.next_round(rng)?,
+                Step::FinalCheck(sumcheck) => return sumcheck.finish().map(|_| ()),
+            }
+        }
+    }
+
+    #[test]
+    fn sumcheck_final_check_opens_the_commitment() {
+        let rng = &mut test_rng();
+        let setup = Setup::generate(5, rng);
+        let f = random_mle(5, rng);
+        let claim = f.evaluations().iter().sum();
+        let oracle = PstOracle::new(&setup, &f, setup.commit(&f));
+        assert_eq!(run(f.clone(), oracle, claim, rng), Ok(()));
+
+        // the rounds are run on another polynomial with the same sum as the committed one
+        let mut evaluations = f.evaluations().to_vec();
+        evaluations[0] += Fr::one();
+        evaluations[3] -= Fr::one();
+        let g = DenseMLE::new(5, evaluations);
+        let oracle = PstOracle::new(&setup, &f, setup.commit(&f));
+        assert_eq!(
+            run(g, oracle, claim, rng),
+            Err(SumcheckError::FinalCheckFailed)
+        );
+    }
+}
@@ -0,0 +1,383 @@
+use std::fmt;
+
+use ark_ff::Field;
+use ark_poly::{
+    polynomial::multivariate::{SparsePolynomial as SparseMVPolynomial, SparseTerm, Term},
+    univariate::DensePolynomial,
+    DenseMVPolynomial,
+};
+use ark_std::rand::RngCore;
+
+use crate::lagrange::univariate::univariate::{interpolate, node};
+use crate::sumcheck::{
+    oracle::{Oracle, OracleError},
+    run_sumcheck, SumcheckError, SumcheckPolynomial, Transcript,
+};
+
+/// A Boolean formula in conjunctive normal form. Literals follow DIMACS: `v` is the variable `x_v`
+/// and `-v` its negation, for `v` in `1..=num_vars`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cnf {
+    pub num_vars: usize,
+    pub clauses: Vec<Vec<i64>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DimacsError {
+    /// No `p cnf <variables> <clauses>` line before the first clause.
+    MissingProblemLine,
+    InvalidProblemLine(String),
+    InvalidLiteral(String),
+    /// A literal mentions a variable past the number declared in the problem line.
+    VariableOutOfRange {
+        variable: usize,
+        num_vars: usize,
+    },
+    ClauseCountMismatch {
+        declared: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for DimacsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DimacsError::MissingProblemLine => write!(f, "missing problem line"),
+            DimacsError::InvalidProblemLine(line) => write!(f, "invalid problem line: {}", line),
+            DimacsError::InvalidLiteral(literal) => write!(f, "invalid literal: {}", literal),
+            DimacsError::VariableOutOfRange { variable, num_vars } => write!(
+                f,
+                "variable {} out of range, the formula has {} variables",
+                variable, num_vars
+            ),
+            DimacsError::ClauseCountMismatch { declared, actual } => write!(
+                f,
+                "problem line declares {} clauses but there are {}",
+                declared, actual
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DimacsError {}
+
+/// Parse a formula in the DIMACS CNF format: `c` comment lines, a `p cnf <variables> <clauses>` line,
+/// then clauses as literals terminated by `0`. A clause may span several lines, and a `%` line
+/// (as in the SATLIB benchmarks) ends the input.
+pub fn parse_dimacs(input: &str) -> Result<Cnf, DimacsError> {
+    let mut header: Option<(usize, usize)> = None;
+    let mut clauses = vec![];
+    let mut clause = vec![];
+    for line in input.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('c') {
+            continue;
+        }
+        if line.starts_with('%') {
+            break;
+        }
+        if line.starts_with('p') {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let parsed = match fields[..] {
+                ["p", "cnf", vars, count] => vars.parse().ok().zip(count.parse().ok()),
+                _ => None,
+            };
+            header = Some(parsed.ok_or_else(|| DimacsError::InvalidProblemLine(line.into()))?);
+            continue;
+        }
+        let (num_vars, _) = header.ok_or(DimacsError::MissingProblemLine)?;
+        for token in line.split_whitespace() {
+            let literal: i64 = token
+                .parse()
+                .map_err(|_| DimacsError::InvalidLiteral(token.into()))?;
+            if literal == 0 {
+                clauses.push(std::mem::take(&mut clause));
+                continue;
+            }
+            let variable = literal.unsigned_abs() as usize;
+            if variable > num_vars {
+                return Err(DimacsError::VariableOutOfRange { variable, num_vars });
+            }
+            clause.push(literal);
+        }
+    }
+    // the terminating 0 of the last clause is often left out
+    if !clause.is_empty() {
+        clauses.push(clause);
+    }
+    let (num_vars, declared) = header.ok_or(DimacsError::MissingProblemLine)?;
+    if clauses.len() != declared {
+        return Err(DimacsError::ClauseCountMismatch {
+            declared,
+            actual: clauses.len(),
+        });
+    }
+    Ok(Cnf { num_vars, clauses })
+}
+
+impl Cnf {
+    /// Whether the assignment with `x_v` at bit `num_vars - v` of `assignment` satisfies the formula,
+    /// so `x_1` is the most significant bit like in the sumcheck hypercube.
+    pub fn is_satisfied_by(&self, assignment: u64) -> bool {
+        self.clauses.iter().all(|clause| {
+            clause.iter().any(|&literal| {
+                let bit = (assignment >> (self.num_vars - literal.unsigned_abs() as usize)) & 1;
+                (bit == 1) == (literal > 0)
+            })
+        })
+    }
+
+    /// The number of satisfying assignments, by trying all `2^n` of them.
+    pub fn count_models(&self) -> u64 {
+        (0..1u64 << self.num_vars)
+            .filter(|&assignment| self.is_satisfied_by(assignment))
+            .count() as u64
+    }
+
+    /// The degree of the arithmetized formula in each variable: the number of times it occurs.
+    pub fn max_degree(&self) -> usize {
+        let mut occurrences = vec![0; self.num_vars + 1];
+        for literal in self.clauses.iter().flatten() {
+            occurrences[literal.unsigned_abs() as usize] += 1;
+        }
+        occurrences.into_iter().max().unwrap_or(0)
+    }
+}
+
+/// The arithmetization of a CNF formula: `phi(x) = prod_C (1 - prod_{l in C} (1 - l(x)))` with
+/// `l(x) = x_v` for a positive literal and `1 - x_v` for a negative one. On the hypercube `phi` is 1
+/// exactly at the satisfying assignments, so its sum is the model count.
+///
+/// Expanding `phi` into monomials blows up exponentially in the number of clauses, so the prover
+/// works with evaluations instead: each round evaluates `phi` at `(r_1, ..., r_{i-1}, t, b)` for
+/// `t = 0..=d` and every `b`, which is `O(d * 2^(n - i) * |phi|)` for round `i`.
+#[derive(Debug, Clone)]
+pub struct ArithmetizedCnf<F: Field> {
+    cnf: Cnf,
+    challenges: Vec<F>,
+}
+
+impl<F: Field> ArithmetizedCnf<F> {
+    pub fn new(cnf: Cnf) -> Self {
+        ArithmetizedCnf {
+            cnf,
+            challenges: vec![],
+        }
+    }
+
+    /// `phi(point)` for any point, not only on the hypercube.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        self.cnf
+            .clauses
+            .iter()
+            .map(|clause| {
+                F::one()
+                    - clause
+                        .iter()
+                        .map(|&literal| {
+                            let x = point[literal.unsigned_abs() as usize - 1];
+                            // 1 - l(x)
+                            if literal > 0 {
+                                F::one() - x
+                            } else {
+                                x
+                            }
+                        })
+                        .product::<F>()
+            })
+            .product()
+    }
+
+    /// `phi` as a sparse polynomial. Only feasible for small formulas, see above.
+    pub fn to_sparse_polynomial(&self) -> SparseMVPolynomial<F, SparseTerm> {
+        let constant = |c: F| {
+            SparseMVPolynomial::from_coefficients_vec(
+                self.cnf.num_vars,
+                vec![(c, SparseTerm::new(vec![]))],
+            )
+        };
+        let mut phi = constant(F::one());
+        for clause in &self.cnf.clauses {
+            let mut unsatisfied = constant(F::one());
+            for &literal in clause {
+                let var = literal.unsigned_abs() as usize - 1;
+                // 1 - l(x) is 1 - x_v or x_v
+                let mut terms = vec![(F::one(), SparseTerm::new(vec![(var, 1)]))];
+                if literal > 0 {
+                    terms[0].0 = -F::one();
+                    terms.push((F::one(), SparseTerm::new(vec![])));
+                }
+                unsatisfied = multiply(
+                    &unsatisfied,
+                    &SparseMVPolynomial::from_coefficients_vec(self.cnf.num_vars, terms),
+                );
+            }
+            phi = multiply(&phi, &(&constant(F::one()) - &unsatisfied));
+        }
+        phi
+    }
+}
+
+// `SparsePolynomial` has no multiplication, so multiply term by term and let
+// `from_coefficients_vec` combine the like terms
+fn multiply<F: Field>(
+    a: &SparseMVPolynomial<F, SparseTerm>,
+    b: &SparseMVPolynomial<F, SparseTerm>,
+) -> SparseMVPolynomial<F, SparseTerm> {
+    let mut terms = vec![];
+    for (c_a, term_a) in a.terms() {
+        for (c_b, term_b) in b.terms() {
+            let powers: Vec<(usize, usize)> = term_a.iter().chain(term_b.iter()).copied().collect();
+            terms.push((*c_a * c_b, SparseTerm::new(powers)));
+        }
+    }
+    SparseMVPolynomial::from_coefficients_vec(DenseMVPolynomial::num_vars(a), terms)
+}
+
+impl<F: Field> SumcheckPolynomial<F> for ArithmetizedCnf<F> {
+    fn num_vars(&self) -> usize {
+        self.cnf.num_vars
+    }
+
+    fn max_degree(&self) -> usize {
+        self.cnf.max_degree()
+    }
+
+    fn round_polynomial(&self, _round: usize, _challenges: &[F]) -> DensePolynomial<F> {
+        let n = self.cnf.num_vars;
+        let fixed = self.challenges.len();
+        let free = n - fixed - 1;
+        let degree = self.cnf.max_degree();
+
+        let mut point = self.challenges.clone();
+        point.resize(n, F::zero());
+        let mut evals = vec![F::zero(); degree + 1];
+        for b in 0..1u64 << free {
+            for (j, x) in point[fixed + 1..].iter_mut().enumerate() {
+                *x = F::from((b >> (free - 1 - j)) & 1);
+            }
+            for (t, eval) in evals.iter_mut().enumerate() {
+                point[fixed] = node(t);
+                *eval += self.evaluate(&point);
+            }
+        }
+        interpolate(&evals)
+    }
+
+    fn fix_variable(&mut self, r: F) {
+        self.challenges.push(r);
+    }
+}
+
+impl<F: Field> Oracle<F> for ArithmetizedCnf<F> {
+    fn evaluate(&self, point: &[F]) -> Result<F, OracleError> {
+        if point.len() != self.cnf.num_vars {
+            return Err(OracleError::InvalidPoint {
+                expected: self.cnf.num_vars,
+                got: point.len(),
+            });
+        }
+        Ok(ArithmetizedCnf::evaluate(self, point))
+    }
+
+    fn num_vars(&self) -> Option<usize> {
+        Some(self.cnf.num_vars)
+    }
+}
+
+/// Prove that `cnf` has `count` satisfying assignments. The field has to be larger than `2^n`,
+/// otherwise the count is only proven modulo its characteristic.
+pub fn prove_model_count<F: Field>(
+    cnf: &Cnf,
+    count: u64,
+    rng: &mut dyn RngCore,
+) -> Result<Transcript<F>, SumcheckError> {
+    run_sumcheck(ArithmetizedCnf::new(cnf.clone()), F::from(count), rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::fields::goldilocks::Goldilocks;
+    use crate::sumcheck::get_claim_fast;
+
+    const EXAMPLE: &str = "c (x1 or not x2) and (x2 or x3 or not x4) and (not x1 or x4)
+p cnf 4 3
+1 -2 0
+2 3 -4 0
+-1
+4 0
+";
+
+    #[test]
+    fn parse_example() {
+        let cnf = parse_dimacs(EXAMPLE).unwrap();
+        assert_eq!(cnf.num_vars, 4);
+        assert_eq!(cnf.clauses, vec![vec![1, -2], vec![2, 3, -4], vec![-1, 4]]);
+        assert_eq!(cnf.max_degree(), 2);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse_dimacs("1 2 0"), Err(DimacsError::MissingProblemLine));
+        assert_eq!(
+            parse_dimacs("p cnf 2 1\n1 3 0"),
+            Err(DimacsError::VariableOutOfRange {
+                variable: 3,
+                num_vars: 2
+            })
+        );
+        assert_eq!(
+            parse_dimacs("p cnf 2 2\n1 2 0"),
+            Err(DimacsError::ClauseCountMismatch {
+                declared: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            parse_dimacs("p cnf 2 1\n1 x 0"),
+            Err(DimacsError::InvalidLiteral("x".into()))
+        );
+    }
+
+    #[test]
+    fn arithmetization_counts_models() {
+        let cnf = parse_dimacs(EXAMPLE).unwrap();
+        let phi = ArithmetizedCnf::<Goldilocks>::new(cnf.clone());
+        let sparse = phi.to_sparse_polynomial();
+        assert_eq!(
+            get_claim_fast(&sparse),
+            Goldilocks::from(cnf.count_models())
+        );
+
+        let rng = &mut test_rng();
+        let point: Vec<Goldilocks> = (0..4).map(|_| Goldilocks::rand(rng)).collect();
+        assert_eq!(
+            phi.evaluate(&point),
+            ark_poly::Polynomial::evaluate(&sparse, &point)
+        );
+    }
+
+    #[test]
+    fn prove_model_count_of_example() {
+        let cnf = parse_dimacs(EXAMPLE).unwrap();
+        let count = cnf.count_models();
+        let rng = &mut test_rng();
+        let transcript = prove_model_count::<Goldilocks>(&cnf, count, rng).unwrap();
+        assert_eq!(transcript.claim, Goldilocks::from(count));
+
+        assert_eq!(
+            prove_model_count::<Goldilocks>(&cnf, count + 1, rng),
+            Err(SumcheckError::ClaimMismatch { round: 0 })
+        );
+    }
+
+    #[test]
+    fn unsatisfiable_formula_has_no_models() {
+        let cnf = parse_dimacs("p cnf 2 4\n1 2 0\n-1 2 0\n1 -2 0\n-1 -2 0\n").unwrap();
+        assert_eq!(cnf.count_models(), 0);
+        prove_model_count::<Goldilocks>(&cnf, 0, &mut test_rng()).unwrap();
+    }
+}
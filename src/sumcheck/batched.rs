@@ -0,0 +1,315 @@
+use ark_ff::Field;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::rand::RngCore;
+
+use super::{PolynomialInfo, Prover, SubClaim, SumcheckError, SumcheckPolynomial, Verifier};
+
+/// `sum_k rho_k * g_k`, run as a single sumcheck over `n = max_k n_k` variables.
+///
+/// A `g_k` over `n_k < n` variables is treated as a polynomial in the *last* `n_k` of the shared
+/// variables that ignores the first `n - n_k`. Its sum over {0,1}^n is therefore `2^(n - n_k) * c_k`,
+/// and its subclaim is at the last `n_k` coordinates of the shared point.
+pub struct BatchedPolynomial<F: Field, P: SumcheckPolynomial<F>> {
+    polys: Vec<P>,
+    claims: Vec<F>,
+    coefficients: Vec<F>,
+    num_vars: usize,
+    // number of variables that have been bound to a challenge so far
+    fixed: usize,
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> BatchedPolynomial<F, P> {
+    // the number of leading dummy variables of `g_k`
+    fn offset(&self, k: usize) -> usize {
+        self.num_vars - self.polys[k].num_vars()
+    }
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> SumcheckPolynomial<F> for BatchedPolynomial<F, P> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn max_degree(&self) -> usize {
+        self.polys.iter().map(|g| g.max_degree()).max().unwrap_or(0)
+    }
+
+    fn check_num_vars(&self) -> Result<(), SumcheckError> {
+        self.polys.iter().try_for_each(|g| g.check_num_vars())
+    }
+
+    fn round_polynomial(&self, round: usize, challenges: &[F]) -> DensePolynomial<F> {
+        let mut accumulator = DensePolynomial::from_coefficients_vec(vec![]);
+        for (k, g) in self.polys.iter().enumerate() {
+            let offset = self.offset(k);
+            if round < offset {
+                // g_k doesn't depend on this variable: what's left of the dummy variables contributes a factor of 2 each
+                let remaining_dummies = (offset - round - 1) as u64;
+                let constant = self.claims[k] * F::from(2u64).pow([remaining_dummies]);
+                accumulator +=
+                    &DensePolynomial::from_coefficients_vec(vec![self.coefficients[k] * constant]);
+            } else {
+                accumulator += (
+                    self.coefficients[k],
+                    &g.round_polynomial(round - offset, &challenges[offset..]),
+                );
+            }
+        }
+        accumulator
+    }
+
+    fn fix_variable(&mut self, r: F) {
+        for k in 0..self.polys.len() {
+            if self.fixed >= self.offset(k) {
+                self.polys[k].fix_variable(r);
+            }
+        }
+        self.fixed += 1;
+    }
+}
+
+/// Proves `sum_x g_k(x) = c_k` for every `k` with one sumcheck, using the verifier's coefficients `rho_k`.
+pub struct BatchedProver<F: Field, P: SumcheckPolynomial<F>> {
+    prover: Prover<F, BatchedPolynomial<F, P>>,
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> BatchedProver<F, P> {
+    pub fn init(
+        polys: Vec<P>,
+        claims: Vec<F>,
+        coefficients: Vec<F>,
+    ) -> Result<Self, SumcheckError> {
+        assert_eq!(polys.len(), claims.len(), "need one claim per polynomial");
+        assert_eq!(
+            polys.len(),
+            coefficients.len(),
+            "need one coefficient per polynomial"
+        );
+        let num_vars = polys.iter().map(|g| g.num_vars()).max().unwrap_or(0);
+        let batched = BatchedPolynomial {
+            polys,
+            claims,
+            coefficients,
+            num_vars,
+            fixed: 0,
+        };
+        Ok(BatchedProver {
+            prover: Prover::init(batched)?,
+        })
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.prover.num_vars()
+    }
+
+    pub fn prove_round(&mut self, r: Option<F>) -> DensePolynomial<F> {
+        self.prover.prove_round(r)
+    }
+
+    /// After the last round, the value of every `g_k` at its part of the shared point `(r_1, ..., r_n)`.
+    /// `r_n` is the verifier's challenge for the last round.
+    pub fn final_evaluations(&self, r_n: F) -> Vec<F> {
+        let batched = &self.prover.g;
+        let challenges = &self.prover.challenges;
+        (0..batched.polys.len())
+            .map(|k| {
+                let offset = batched.offset(k);
+                let g = &batched.polys[k];
+                if g.num_vars() == 0 {
+                    return batched.claims[k];
+                }
+                // every variable but the last is bound, so the last round polynomial is g_k(r_k..., X)
+                g.round_polynomial(g.num_vars() - 1, &challenges[offset..])
+                    .evaluate(&r_n)
+            })
+            .collect()
+    }
+}
+
+pub struct BatchedVerifier<F: Field> {
+    verifier: Verifier<F>,
+    infos: Vec<PolynomialInfo>,
+    coefficients: Vec<F>,
+}
+
+impl<F: Field> BatchedVerifier<F> {
+    /// Sample a random coefficient per claim and combine the claims into one.
+    pub fn init(claims: &[(F, PolynomialInfo)], rng: &mut dyn RngCore) -> Self {
+        let num_vars = claims
+            .iter()
+            .map(|(_, info)| info.num_vars)
+            .max()
+            .unwrap_or(0);
+        let max_degree = claims
+            .iter()
+            .map(|(_, info)| info.max_degree)
+            .max()
+            .unwrap_or(0);
+        let coefficients: Vec<F> = claims.iter().map(|_| F::rand(rng)).collect();
+        let combined_claim = claims
+            .iter()
+            .zip(&coefficients)
+            .map(|((c, info), rho)| {
+                *rho * c * F::from(2u64).pow([(num_vars - info.num_vars) as u64])
+            })
+            .sum();
+        BatchedVerifier {
+            verifier: Verifier::init_deferred(
+                combined_claim,
+                PolynomialInfo {
+                    num_vars,
+                    max_degree,
+                },
+            ),
+            infos: claims.iter().map(|(_, info)| *info).collect(),
+            coefficients,
+        }
+    }
+
+    /// The coefficients `rho_k` the prover needs to combine its polynomials.
+    pub fn coefficients(&self) -> &[F] {
+        &self.coefficients
+    }
+
+    pub fn verify_round(
+        &mut self,
+        current_poly: DensePolynomial<F>,
+        rng: &mut dyn RngCore,
+    ) -> Result<Option<F>, SumcheckError> {
+        self.verifier.verify_round(current_poly, rng)
+    }
+
+    /// Check the prover's claimed evaluations `g_k(r_k) = v_k` against the final round, and split the
+    /// combined subclaim into one subclaim per polynomial for the caller to discharge.
+    pub fn finalize(&self, evaluations: &[F]) -> Result<Vec<SubClaim<F>>, SumcheckError> {
+        let subclaim = self
+            .verifier
+            .subclaim()
+            .ok_or(SumcheckError::ProtocolNotFinished)?;
+        if evaluations.len() != self.infos.len() {
+            return Err(SumcheckError::FinalCheckFailed);
+        }
+        let combined: F = evaluations
+            .iter()
+            .zip(&self.coefficients)
+            .map(|(v, rho)| *v * rho)
+            .sum();
+        if combined != subclaim.expected_value {
+            return Err(SumcheckError::FinalCheckFailed);
+        }
+        let num_vars = subclaim.point.len();
+        Ok(self
+            .infos
+            .iter()
+            .zip(evaluations)
+            .map(|(info, v)| SubClaim {
+                point: subclaim.point[num_vars - info.num_vars..].to_vec(),
+                expected_value: *v,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::One;
+    use ark_poly::{
+        multivariate::{SparsePolynomial as SparseMVPolynomial, SparseTerm, Term},
+        DenseMVPolynomial,
+    };
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::sumcheck::test_utils::Fq;
+    use crate::sumcheck::util::util::get_claim;
+
+    fn sample_polys() -> Vec<SparseMVPolynomial<Fq, SparseTerm>> {
+        vec![
+            // 2 x_1^3 + x_1 x_3 + x_2 x_3
+            SparseMVPolynomial::from_coefficients_slice(
+                3,
+                &[
+                    (Fq::from(2), SparseTerm::new(vec![(0, 3)])),
+                    (Fq::from(1), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                    (Fq::from(1), SparseTerm::new(vec![(1, 1), (2, 1)])),
+                ],
+            ),
+            // 5 x_1 x_2 + 3 x_2^2 + 1
+            SparseMVPolynomial::from_coefficients_slice(
+                2,
+                &[
+                    (Fq::from(5), SparseTerm::new(vec![(0, 1), (1, 1)])),
+                    (Fq::from(3), SparseTerm::new(vec![(1, 2)])),
+                    (Fq::from(1), SparseTerm::new(vec![])),
+                ],
+            ),
+            // 4 x_1 + 7
+            SparseMVPolynomial::from_coefficients_slice(
+                1,
+                &[
+                    (Fq::from(4), SparseTerm::new(vec![(0, 1)])),
+                    (Fq::from(7), SparseTerm::new(vec![])),
+                ],
+            ),
+        ]
+    }
+
+    fn run_batched(
+        polys: Vec<SparseMVPolynomial<Fq, SparseTerm>>,
+        claims: Vec<Fq>,
+        tamper: bool,
+    ) -> Result<Vec<SubClaim<Fq>>, SumcheckError> {
+        let rng = &mut test_rng();
+        let claims_with_info: Vec<(Fq, PolynomialInfo)> = claims
+            .iter()
+            .zip(&polys)
+            .map(|(c, g)| (*c, g.info()))
+            .collect();
+        let mut verifier = BatchedVerifier::init(&claims_with_info, rng);
+        let mut prover = BatchedProver::init(polys, claims, verifier.coefficients().to_vec())?;
+
+        let mut r = None;
+        for _ in 0..prover.num_vars() {
+            r = verifier.verify_round(prover.prove_round(r), rng)?;
+        }
+        let mut evaluations = prover.final_evaluations(r.unwrap());
+        if tamper {
+            evaluations[0] += Fq::one();
+        }
+        verifier.finalize(&evaluations)
+    }
+
+    #[test]
+    fn batched_protocol_with_different_num_vars() {
+        let polys = sample_polys();
+        let claims: Vec<Fq> = polys.iter().map(|g| get_claim(g.clone())).collect();
+        let subclaims = run_batched(polys.clone(), claims, false).unwrap();
+
+        assert_eq!(subclaims.len(), 3);
+        for (subclaim, g) in subclaims.iter().zip(&polys) {
+            assert_eq!(subclaim.point.len(), DenseMVPolynomial::num_vars(g));
+            assert!(subclaim.check(g).unwrap());
+        }
+        // all subclaims are at suffixes of the same point
+        assert_eq!(subclaims[0].point[1..], subclaims[1].point[..]);
+        assert_eq!(subclaims[0].point[2..], subclaims[2].point[..]);
+    }
+
+    #[test]
+    fn batched_protocol_rejects_wrong_claim() {
+        let polys = sample_polys();
+        let mut claims: Vec<Fq> = polys.iter().map(|g| get_claim(g.clone())).collect();
+        claims[2] += Fq::one();
+        assert!(run_batched(polys, claims, false).is_err());
+    }
+
+    #[test]
+    fn batched_protocol_rejects_wrong_evaluations() {
+        let polys = sample_polys();
+        let claims: Vec<Fq> = polys.iter().map(|g| get_claim(g.clone())).collect();
+        assert_eq!(
+            run_batched(polys, claims, true),
+            Err(SumcheckError::FinalCheckFailed)
+        );
+    }
+}
@@ -0,0 +1,341 @@
+use ark_ff::Field;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::rand::RngCore;
+
+use super::{oracle, PolynomialInfo, Prover, SumcheckError, SumcheckPolynomial, Verifier};
+
+/// A prover that deviates from the protocol, used to check that the verifier rejects it.
+pub trait CheatingProver<F: Field> {
+    /// The claim sent to the verifier before the first round.
+    fn claim(&self) -> F;
+
+    fn num_vars(&self) -> usize;
+
+    /// Same interface as `Prover::prove_round`.
+    fn prove_round(&mut self, r: Option<F>) -> DensePolynomial<F>;
+}
+
+// Runs the honest prover alongside the cheater. The "debt" of a round is how far the sum the verifier
+// expects is from the sum of the honest `g_i`, which the cheater has to hide in the polynomial it sends.
+struct Deviation<F: Field, P: SumcheckPolynomial<F>> {
+    honest: Prover<F, P>,
+    claim: F,
+    true_claim: F,
+    sent: Option<DensePolynomial<F>>,
+    honest_previous: Option<DensePolynomial<F>>,
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> Deviation<F, P> {
+    fn init(g: P, true_claim: F, claim: F) -> Result<Self, SumcheckError> {
+        Ok(Deviation {
+            honest: Prover::init(g)?,
+            claim,
+            true_claim,
+            sent: None,
+            honest_previous: None,
+        })
+    }
+
+    // the honest g_i and this round's debt
+    fn next_round(&mut self, r: Option<F>) -> (DensePolynomial<F>, F) {
+        let honest = self.honest.prove_round(r);
+        let debt = match (&self.sent, &self.honest_previous, r) {
+            (Some(sent), Some(previous), Some(r)) => sent.evaluate(&r) - previous.evaluate(&r),
+            _ => self.claim - self.true_claim,
+        };
+        (honest, debt)
+    }
+
+    fn send(&mut self, honest: DensePolynomial<F>, poly: DensePolynomial<F>) -> DensePolynomial<F> {
+        self.honest_previous = Some(honest);
+        self.sent = Some(poly.clone());
+        poly
+    }
+}
+
+// `prod_{a in roots} (X - a)`
+fn vanishing<F: Field>(roots: &[F]) -> DensePolynomial<F> {
+    roots.iter().fold(
+        DensePolynomial::from_coefficients_vec(vec![F::one()]),
+        |acc, a| acc.naive_mul(&DensePolynomial::from_coefficients_vec(vec![-*a, F::one()])),
+    )
+}
+
+/// `debt * L(X)` with `L(0) + L(1) = 1`, so adding it to `g_i` matches the sum the verifier expects.
+/// `L` vanishes on `roots`, so if the verifier's challenge is one of them the debt is gone and the
+/// cheater can finish honestly.
+fn repay<F: Field>(debt: F, roots: &[F]) -> DensePolynomial<F> {
+    let vanishing = vanishing(roots);
+    let scale = debt
+        * (vanishing.evaluate(&F::zero()) + vanishing.evaluate(&F::one()))
+            .inverse()
+            .expect("L(0) + L(1) should be nonzero for the chosen roots");
+    DensePolynomial::from_coefficients_vec(vanishing.coeffs.iter().map(|c| *c * scale).collect())
+}
+
+/// Claims `c + offset` and then sends the honest `g_i`, so the first round's sum gives it away.
+pub struct WrongClaim<F: Field, P: SumcheckPolynomial<F>> {
+    deviation: Deviation<F, P>,
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> WrongClaim<F, P> {
+    /// `true_claim` is the actual sum of `g`.
+    pub fn init(g: P, true_claim: F, offset: F) -> Result<Self, SumcheckError> {
+        Ok(WrongClaim {
+            deviation: Deviation::init(g, true_claim, true_claim + offset)?,
+        })
+    }
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> CheatingProver<F> for WrongClaim<F, P> {
+    fn claim(&self) -> F {
+        self.deviation.claim
+    }
+
+    fn num_vars(&self) -> usize {
+        self.deviation.honest.num_vars()
+    }
+
+    fn prove_round(&mut self, r: Option<F>) -> DensePolynomial<F> {
+        let (honest, _) = self.deviation.next_round(r);
+        self.deviation.send(honest.clone(), honest)
+    }
+}
+
+/// Claims the true sum, but sends `g_1 + shift * X (X - 1)` in the first round: correct at 0 and 1,
+/// wrong everywhere else. From then on it repays the debt with a linear `L` vanishing at 0.
+pub struct ShiftedPolynomial<F: Field, P: SumcheckPolynomial<F>> {
+    deviation: Deviation<F, P>,
+    shift: F,
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> ShiftedPolynomial<F, P> {
+    pub fn init(g: P, true_claim: F, shift: F) -> Result<Self, SumcheckError> {
+        Ok(ShiftedPolynomial {
+            deviation: Deviation::init(g, true_claim, true_claim)?,
+            shift,
+        })
+    }
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> CheatingProver<F> for ShiftedPolynomial<F, P> {
+    fn claim(&self) -> F {
+        self.deviation.claim
+    }
+
+    fn num_vars(&self) -> usize {
+        self.deviation.honest.num_vars()
+    }
+
+    fn prove_round(&mut self, r: Option<F>) -> DensePolynomial<F> {
+        let (honest, debt) = self.deviation.next_round(r);
+        let poly = match r {
+            None => {
+                &honest
+                    + &DensePolynomial::from_coefficients_vec(vec![
+                        F::zero(),
+                        -self.shift,
+                        self.shift,
+                    ])
+            }
+            Some(_) => &honest + &repay(debt, &[F::zero()]),
+        };
+        self.deviation.send(honest, poly)
+    }
+}
+
+/// Claims `c + offset` and hides the debt in a polynomial that vanishes on `guesses`. Every round the
+/// verifier picks one of the guesses, the debt disappears, so with `d` guesses per round it is
+/// accepted with probability about `d * n / |F|`, the Schwartz–Zippel bound.
+pub struct GuessChallenge<F: Field, P: SumcheckPolynomial<F>> {
+    deviation: Deviation<F, P>,
+    guesses: Vec<F>,
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> GuessChallenge<F, P> {
+    /// Fails if `guesses` is empty or `L(0) + L(1) = 0` for the polynomial vanishing on them,
+    /// e.g. for `{0, 1}` or `{1/2}`, since the debt can't be scaled into such an `L`.
+    pub fn init(g: P, true_claim: F, offset: F, guesses: Vec<F>) -> Result<Self, SumcheckError> {
+        let l = vanishing(&guesses);
+        if guesses.is_empty() || (l.evaluate(&F::zero()) + l.evaluate(&F::one())).is_zero() {
+            return Err(SumcheckError::InvalidGuesses);
+        }
+        Ok(GuessChallenge {
+            deviation: Deviation::init(g, true_claim, true_claim + offset)?,
+            guesses,
+        })
+    }
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> CheatingProver<F> for GuessChallenge<F, P> {
+    fn claim(&self) -> F {
+        self.deviation.claim
+    }
+
+    fn num_vars(&self) -> usize {
+        self.deviation.honest.num_vars()
+    }
+
+    fn prove_round(&mut self, r: Option<F>) -> DensePolynomial<F> {
+        let (honest, debt) = self.deviation.next_round(r);
+        let poly = &honest + &repay(debt, &self.guesses);
+        self.deviation.send(honest, poly)
+    }
+}
+
+/// `GuessChallenge` with `d + 1` guesses per round, which would beat the Schwartz–Zippel bound if the
+/// verifier didn't enforce the degree bound on `g_i`.
+pub struct DegreeInflation<F: Field, P: SumcheckPolynomial<F>>(GuessChallenge<F, P>);
+
+impl<F: Field, P: SumcheckPolynomial<F>> DegreeInflation<F, P> {
+    pub fn init(g: P, true_claim: F, offset: F) -> Result<Self, SumcheckError> {
+        // avoid 0 and 1, where L(0) + L(1) would be 0
+        let guesses = (2..g.max_degree() as u64 + 3).map(F::from).collect();
+        Ok(DegreeInflation(GuessChallenge::init(
+            g, true_claim, offset, guesses,
+        )?))
+    }
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> CheatingProver<F> for DegreeInflation<F, P> {
+    fn claim(&self) -> F {
+        self.0.claim()
+    }
+
+    fn num_vars(&self) -> usize {
+        self.0.num_vars()
+    }
+
+    fn prove_round(&mut self, r: Option<F>) -> DensePolynomial<F> {
+        self.0.prove_round(r)
+    }
+}
+
+/// How often a cheating prover convinced the verifier.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoundnessReport {
+    pub trials: usize,
+    pub accepted: usize,
+    /// The soundness error `d * n / |F|` from Schwartz–Zippel.
+    pub bound: f64,
+}
+
+impl SoundnessReport {
+    pub fn acceptance_rate(&self) -> f64 {
+        self.accepted as f64 / self.trials as f64
+    }
+}
+
+/// `|F|` as a float, which is only meaningful for the small fields where the acceptance rate is measurable.
+fn field_size<F: Field>() -> f64 {
+    let p = F::characteristic()
+        .iter()
+        .rev()
+        .fold(0.0, |acc, &limb| acc * 2f64.powi(64) + limb as f64);
+    p.powi(F::extension_degree() as i32)
+}
+
+/// Run the protocol `trials` times, each time with a fresh prover from `new_prover` and a fresh verifier
+/// with access to `oracle`, and count how often the verifier accepts.
+pub fn measure_soundness<F, O, C>(
+    oracle: &O,
+    info: PolynomialInfo,
+    trials: usize,
+    mut new_prover: impl FnMut() -> C,
+    rng: &mut dyn RngCore,
+) -> Result<SoundnessReport, SumcheckError>
+where
+    F: Field,
+    O: oracle::Oracle<F> + Clone,
+    C: CheatingProver<F>,
+{
+    let mut accepted = 0;
+    for _ in 0..trials {
+        let mut prover = new_prover();
+        let mut verifier = Verifier::init(oracle.clone(), prover.claim(), info)?;
+        let mut r = None;
+        let result = (0..prover.num_vars()).try_for_each(|_| {
+            r = verifier.verify_round(prover.prove_round(r), rng)?;
+            Ok::<_, SumcheckError>(())
+        });
+        if result.is_ok() {
+            accepted += 1;
+        }
+    }
+    Ok(SoundnessReport {
+        trials,
+        accepted,
+        bound: (info.max_degree * info.num_vars) as f64 / field_size::<F>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::One;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::sumcheck::test_utils::{sample_poly, Fq};
+
+    const TRIALS: usize = 1000;
+
+    fn measure<C: CheatingProver<Fq>>(new_prover: impl FnMut() -> C) -> SoundnessReport {
+        let g = sample_poly();
+        measure_soundness(&g, g.info(), TRIALS, new_prover, &mut test_rng()).unwrap()
+    }
+
+    #[test]
+    fn bound_for_small_field() {
+        let report = measure(|| WrongClaim::init(sample_poly(), Fq::from(12), Fq::one()).unwrap());
+        assert_eq!(report.bound, 9.0 / 71.0);
+    }
+
+    #[test]
+    fn wrong_claim_always_rejected() {
+        let report = measure(|| WrongClaim::init(sample_poly(), Fq::from(12), Fq::one()).unwrap());
+        assert_eq!(report.accepted, 0);
+    }
+
+    #[test]
+    fn degree_inflation_always_rejected() {
+        let report =
+            measure(|| DegreeInflation::init(sample_poly(), Fq::from(12), Fq::one()).unwrap());
+        assert_eq!(report.accepted, 0);
+    }
+
+    #[test]
+    fn shifted_polynomial_rarely_accepted() {
+        let report =
+            measure(|| ShiftedPolynomial::init(sample_poly(), Fq::from(12), Fq::one()).unwrap());
+        assert!(report.accepted > 0);
+        assert!(report.acceptance_rate() <= report.bound);
+    }
+
+    #[test]
+    fn guesses_that_cannot_repay_are_rejected() {
+        let init = |guesses: Vec<Fq>| {
+            GuessChallenge::init(sample_poly(), Fq::from(12), Fq::one(), guesses).err()
+        };
+        assert_eq!(init(vec![]), Some(SumcheckError::InvalidGuesses));
+        assert_eq!(
+            init(vec![Fq::from(0), Fq::from(1)]),
+            Some(SumcheckError::InvalidGuesses)
+        );
+        assert_eq!(
+            init(vec![Fq::from(2).inverse().unwrap()]),
+            Some(SumcheckError::InvalidGuesses)
+        );
+    }
+
+    #[test]
+    fn guessing_approaches_bound() {
+        let guesses: Vec<Fq> = (2..5u64).map(Fq::from).collect();
+        let report = measure(|| {
+            GuessChallenge::init(sample_poly(), Fq::from(12), Fq::one(), guesses.clone()).unwrap()
+        });
+        // three guesses in each of three rounds: 1 - (1 - 3/71)^3 is just below 9/71
+        assert!(report.acceptance_rate() > report.bound / 2.0);
+        // leave room for sampling error
+        assert!(report.acceptance_rate() <= report.bound * 1.5);
+    }
+}
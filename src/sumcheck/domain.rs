@@ -0,0 +1,52 @@
+use ark_ff::Field;
+use ark_poly::{univariate::DensePolynomial, Polynomial};
+
+/// The set `H` that every variable is summed over, so the protocol proves `sum_{x in H^n} g(x) = c`.
+/// The classic protocol uses `H = {0, 1}`; larger sets pair with low-degree extensions over bigger grids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumDomain<F: Field> {
+    points: Vec<F>,
+}
+
+impl<F: Field> SumDomain<F> {
+    pub fn new(points: Vec<F>) -> Self {
+        assert!(!points.is_empty(), "domain should not be empty");
+        for (i, p) in points.iter().enumerate() {
+            assert!(!points[..i].contains(p), "domain points should be distinct");
+        }
+        SumDomain { points }
+    }
+
+    /// `{0, 1}`
+    pub fn boolean() -> Self {
+        Self::range(2)
+    }
+
+    /// `{0, 1, ..., k - 1}`
+    pub fn range(k: u64) -> Self {
+        Self::new((0..k).map(F::from).collect())
+    }
+
+    pub fn points(&self) -> &[F] {
+        &self.points
+    }
+
+    pub fn size(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_boolean(&self) -> bool {
+        self.points == [F::zero(), F::one()]
+    }
+
+    /// `sum_{h in H} p(h)`, the verifier's round check.
+    pub fn sum(&self, p: &DensePolynomial<F>) -> F {
+        self.points.iter().map(|h| p.evaluate(h)).sum()
+    }
+}
+
+impl<F: Field> Default for SumDomain<F> {
+    fn default() -> Self {
+        Self::boolean()
+    }
+}
@@ -0,0 +1,220 @@
+use std::marker::PhantomData;
+
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+use ark_std::rand::RngCore;
+
+use super::{oracle, Prover, SubClaim, SumcheckError, SumcheckPolynomial, Verifier};
+
+/// Everything exchanged during one run of the protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript<F: Field> {
+    pub claim: F,
+    /// `g_1, ..., g_n` as sent by the prover.
+    pub round_polynomials: Vec<DensePolynomial<F>>,
+    /// `r_1, ..., r_n` as sampled by the verifier.
+    pub challenges: Vec<F>,
+    /// `g(r_1, ..., r_n)`, confirmed by the final oracle query.
+    pub final_evaluation: F,
+}
+
+/// Run an honest prover against a verifier that queries `g` itself at the end.
+pub fn run_sumcheck<F, P>(
+    g: P,
+    claim: F,
+    rng: &mut dyn RngCore,
+) -> Result<Transcript<F>, SumcheckError>
+where
+    F: Field,
+    P: SumcheckPolynomial<F> + oracle::Oracle<F> + Clone,
+{
+    let oracle = g.clone();
+    let mut step = Sumcheck::start(g, oracle, claim)?;
+    loop {
+        match step {
+            Step::Round(sumcheck) => step = sumcheck.next_round(rng)?,
+            Step::FinalCheck(sumcheck) => return sumcheck.finish(),
+        }
+    }
+}
+
+/// There are rounds left to run.
+pub struct Rounds;
+/// Every round has been verified, only the oracle query is left.
+pub struct FinalCheck;
+
+/// An honest prover and a verifier run in lockstep, with the protocol's progress in the type.
+///
+/// A `Sumcheck<_, _, _, Rounds>` can only run the next round, and a `Sumcheck<_, _, _, FinalCheck>`
+/// can only run the final check, which is the only way to get a `Transcript`. Challenges are passed
+/// from the verifier to the prover internally.
+pub struct Sumcheck<F: Field, P: SumcheckPolynomial<F>, O: oracle::Oracle<F>, S> {
+    prover: Prover<F, P>,
+    verifier: Verifier<F>,
+    oracle: O,
+    claim: F,
+    round_polynomials: Vec<DensePolynomial<F>>,
+    challenges: Vec<F>,
+    state: PhantomData<S>,
+}
+
+/// The state after starting the protocol or running a round.
+pub enum Step<F: Field, P: SumcheckPolynomial<F>, O: oracle::Oracle<F>> {
+    Round(Sumcheck<F, P, O, Rounds>),
+    FinalCheck(Sumcheck<F, P, O, FinalCheck>),
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>, O: oracle::Oracle<F>, S> Sumcheck<F, P, O, S> {
+    fn into_state<T>(self) -> Sumcheck<F, P, O, T> {
+        Sumcheck {
+            prover: self.prover,
+            verifier: self.verifier,
+            oracle: self.oracle,
+            claim: self.claim,
+            round_polynomials: self.round_polynomials,
+            challenges: self.challenges,
+            state: PhantomData,
+        }
+    }
+
+    // a polynomial without variables has no rounds and goes straight to the final check
+    fn step(self) -> Step<F, P, O> {
+        if self.round_polynomials.len() == self.prover.num_vars() {
+            Step::FinalCheck(self.into_state())
+        } else {
+            Step::Round(self.into_state())
+        }
+    }
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>, O: oracle::Oracle<F>> Sumcheck<F, P, O, Rounds> {
+    /// Prove `sum_{x in {0,1}^n} g(x) = claim`, where `oracle` answers the verifier's query about `g`.
+    pub fn start(g: P, oracle: O, claim: F) -> Result<Step<F, P, O>, SumcheckError> {
+        let info = g.info();
+        if let Some(actual) = oracle.num_vars() {
+            if actual != info.num_vars {
+                return Err(SumcheckError::NumVarsMismatch {
+                    declared: info.num_vars,
+                    actual,
+                });
+            }
+        }
+        let sumcheck: Sumcheck<F, P, O, Rounds> = Sumcheck {
+            prover: Prover::init(g)?,
+            verifier: Verifier::init_deferred(claim, info),
+            oracle,
+            claim,
+            round_polynomials: vec![],
+            challenges: vec![],
+            state: PhantomData,
+        };
+        Ok(sumcheck.step())
+    }
+
+    /// The prover sends `g_i`, the verifier checks it and answers with `r_i`.
+    pub fn next_round(mut self, rng: &mut dyn RngCore) -> Result<Step<F, P, O>, SumcheckError> {
+        let poly_i = self.prover.prove_round(self.challenges.last().copied());
+        self.round_polynomials.push(poly_i.clone());
+        let r = self
+            .verifier
+            .verify_round(poly_i, rng)?
+            .expect("the verifier answers every round with a challenge");
+        self.challenges.push(r);
+        Ok(self.step())
+    }
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>, O: oracle::Oracle<F>> Sumcheck<F, P, O, FinalCheck> {
+    /// Query the oracle at `(r_1, ..., r_n)` and compare with `g_n(r_n)`.
+    #[must_use = "the protocol only succeeds if the final check does"]
+    pub fn finish(self) -> Result<Transcript<F>, SumcheckError> {
+        let subclaim = match self.verifier.subclaim() {
+            Some(subclaim) => subclaim.clone(),
+            // no variables, so the claimed sum is g itself
+            None => SubClaim {
+                point: vec![],
+                expected_value: self.claim,
+            },
+        };
+        if !subclaim.check(&self.oracle)? {
+            return Err(SumcheckError::FinalCheckFailed);
+        }
+        Ok(Transcript {
+            claim: self.claim,
+            round_polynomials: self.round_polynomials,
+            challenges: self.challenges,
+            final_evaluation: subclaim.expected_value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{One, Zero};
+    use ark_poly::{
+        multivariate::{SparsePolynomial as SparseMVPolynomial, SparseTerm, Term},
+        DenseMVPolynomial, Polynomial,
+    };
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::sumcheck::test_utils::{sample_poly, Fq};
+    use crate::sumcheck::{util::util::get_claim, FnOracle};
+
+    #[test]
+    fn run_sumcheck_honest_prover() {
+        let g = sample_poly();
+        let transcript = run_sumcheck(g.clone(), Fq::from(12), &mut test_rng()).unwrap();
+
+        assert_eq!(transcript.round_polynomials.len(), 3);
+        assert_eq!(transcript.challenges.len(), 3);
+        assert_eq!(
+            transcript.final_evaluation,
+            Polynomial::evaluate(&g, &transcript.challenges)
+        );
+    }
+
+    #[test]
+    fn run_sumcheck_wrong_claim() {
+        let g = sample_poly();
+        let claim = get_claim(g.clone()) + Fq::one();
+        assert_eq!(
+            run_sumcheck(g, claim, &mut test_rng()),
+            Err(SumcheckError::ClaimMismatch { round: 0 })
+        );
+    }
+
+    #[test]
+    fn lying_oracle_fails_final_check() {
+        let g = sample_poly();
+        let rng = &mut test_rng();
+        let oracle = FnOracle(|_: &[Fq]| Ok(Fq::zero()));
+        let mut step = Sumcheck::start(g, oracle, Fq::from(12)).unwrap();
+        let mut rounds = 0;
+        let sumcheck = loop {
+            match step {
+                Step::Round(sumcheck) => {
+                    rounds += 1;
+                    step = sumcheck.next_round(rng).unwrap();
+                }
+                Step::FinalCheck(sumcheck) => break sumcheck,
+            }
+        };
+        assert_eq!(rounds, 3);
+        assert_eq!(sumcheck.finish(), Err(SumcheckError::FinalCheckFailed));
+    }
+
+    #[test]
+    fn constant_polynomial_has_no_rounds() {
+        let g = SparseMVPolynomial::from_coefficients_slice(
+            0,
+            &[(Fq::from(5), SparseTerm::new(vec![]))],
+        );
+        let transcript = run_sumcheck(g.clone(), Fq::from(5), &mut test_rng()).unwrap();
+        assert!(transcript.round_polynomials.is_empty());
+        assert_eq!(
+            run_sumcheck(g, Fq::from(6), &mut test_rng()),
+            Err(SumcheckError::FinalCheckFailed)
+        );
+    }
+}
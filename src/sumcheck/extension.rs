@@ -0,0 +1,253 @@
+use ark_ff::Field;
+use ark_poly::{
+    polynomial::multivariate::{SparsePolynomial as SparseMVPolynomial, SparseTerm},
+    univariate::DensePolynomial,
+    DenseMVPolynomial, DenseUVPolynomial,
+};
+
+use super::{
+    oracle::{Oracle, OracleError},
+    MleProduct, SumcheckPolynomial,
+};
+use crate::lagrange::multivariate::multivariate::DenseMLE;
+
+/// `g` with coefficients in the base field `E::BasePrimeField`, as a polynomial over `E`.
+/// The sumcheck is then run as usual with challenges and round polynomials over `E`.
+pub fn lift_polynomial<E: Field>(
+    g: &SparseMVPolynomial<E::BasePrimeField, SparseTerm>,
+) -> SparseMVPolynomial<E, SparseTerm> {
+    SparseMVPolynomial::from_coefficients_vec(
+        DenseMVPolynomial::num_vars(g),
+        g.terms()
+            .iter()
+            .map(|(c, term)| (E::from_base_prime_field(*c), term.clone()))
+            .collect(),
+    )
+}
+
+fn lift_univariate<E: Field>(p: &DensePolynomial<E::BasePrimeField>) -> DensePolynomial<E> {
+    DensePolynomial::from_coefficients_vec(
+        p.coeffs
+            .iter()
+            .map(|c| E::from_base_prime_field(*c))
+            .collect(),
+    )
+}
+
+// `DenseMLE::fix_first_variable` with a challenge from the extension
+fn fold_into_extension<E: Field>(f: &DenseMLE<E::BasePrimeField>, r: E) -> DenseMLE<E> {
+    let half = f.evaluations().len() / 2;
+    let (lo, hi) = f.evaluations().split_at(half);
+    DenseMLE::new(
+        f.num_vars() - 1,
+        lo.iter()
+            .zip(hi)
+            .map(|(lo, hi)| E::from_base_prime_field(*lo) + r * E::from_base_prime_field(*hi - lo))
+            .collect(),
+    )
+}
+
+/// `g = f_1 * ... * f_d` with tables over the base field `B = E::BasePrimeField` and challenges from `E`.
+///
+/// Over a 31- or 64-bit field the soundness error `d * n / |B|` is too large, so the verifier samples
+/// `r_i` from the extension `E` and the round polynomials are over `E`. The first round, which reads
+/// all `2^n` entries, only needs base field arithmetic; folding with `r_1` moves the tables into `E`.
+#[derive(Debug, Clone)]
+pub struct ExtensionMleProduct<E: Field> {
+    tables: Tables<E>,
+}
+
+#[derive(Debug, Clone)]
+enum Tables<E: Field> {
+    Base(MleProduct<E::BasePrimeField>),
+    Extension(MleProduct<E>),
+}
+
+impl<E: Field> ExtensionMleProduct<E> {
+    pub fn new(factors: Vec<DenseMLE<E::BasePrimeField>>) -> Self {
+        ExtensionMleProduct {
+            tables: Tables::Base(MleProduct::new(factors)),
+        }
+    }
+
+    /// `g(point)` for a point over the extension. Only available before the first variable is fixed.
+    pub fn evaluate(&self, point: &[E]) -> Option<E> {
+        match &self.tables {
+            Tables::Base(g) => Some(
+                g.factors()
+                    .iter()
+                    .map(|f| match point.split_first() {
+                        Some((r, rest)) => fold_into_extension(f, *r).evaluate(rest),
+                        None => E::from_base_prime_field(f.evaluations()[0]),
+                    })
+                    .product(),
+            ),
+            Tables::Extension(_) => None,
+        }
+    }
+}
+
+impl<E: Field> SumcheckPolynomial<E> for ExtensionMleProduct<E> {
+    fn num_vars(&self) -> usize {
+        match &self.tables {
+            Tables::Base(g) => g.num_vars(),
+            Tables::Extension(g) => g.num_vars(),
+        }
+    }
+
+    fn max_degree(&self) -> usize {
+        match &self.tables {
+            Tables::Base(g) => g.degree(),
+            Tables::Extension(g) => g.degree(),
+        }
+    }
+
+    fn round_polynomial(&self, round: usize, challenges: &[E]) -> DensePolynomial<E> {
+        match &self.tables {
+            Tables::Base(g) => lift_univariate(&g.round_polynomial(round, &[])),
+            Tables::Extension(g) => g.round_polynomial(round, challenges),
+        }
+    }
+
+    fn fix_variable(&mut self, r: E) {
+        match &mut self.tables {
+            Tables::Base(g) => {
+                let folded = g
+                    .factors()
+                    .iter()
+                    .map(|f| fold_into_extension(f, r))
+                    .collect();
+                self.tables = Tables::Extension(MleProduct::new(folded));
+            }
+            Tables::Extension(g) => g.fix_variable(r),
+        }
+    }
+}
+
+impl<E: Field> Oracle<E> for ExtensionMleProduct<E> {
+    fn evaluate(&self, point: &[E]) -> Result<E, OracleError> {
+        if point.len() != SumcheckPolynomial::num_vars(self) {
+            return Err(OracleError::InvalidPoint {
+                expected: SumcheckPolynomial::num_vars(self),
+                got: point.len(),
+            });
+        }
+        ExtensionMleProduct::evaluate(self, point).ok_or_else(|| {
+            OracleError::Unavailable("the tables have already been folded".to_string())
+        })
+    }
+
+    fn num_vars(&self) -> Option<usize> {
+        Some(SumcheckPolynomial::num_vars(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::One;
+    use ark_poly::multivariate::Term;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::fields::{
+        babybear::{BabyBear, BabyBearExt4},
+        goldilocks::{Goldilocks, GoldilocksExt2},
+        mersenne31::{Mersenne31, Mersenne31Ext3},
+    };
+    use crate::sumcheck::{run_sumcheck, Prover, SumcheckError, Verifier};
+
+    fn random_table<B: Field>(num_vars: usize) -> Vec<DenseMLE<B>> {
+        let rng = &mut test_rng();
+        (0..3)
+            .map(|_| DenseMLE::new(num_vars, (0..1 << num_vars).map(|_| B::rand(rng)).collect()))
+            .collect()
+    }
+
+    fn run_extension_product<E: Field>(
+        factors: Vec<DenseMLE<E::BasePrimeField>>,
+        claim: E,
+    ) -> Result<Vec<DensePolynomial<E>>, SumcheckError> {
+        let rng = &mut test_rng();
+        let g = ExtensionMleProduct::<E>::new(factors);
+        let mut verifier = Verifier::init(g.clone(), claim, g.info())?;
+        let mut prover = Prover::init(g)?;
+        let mut r = None;
+        let mut round_polynomials = vec![];
+        for _ in 0..prover.num_vars() {
+            let poly = prover.prove_round(r);
+            round_polynomials.push(poly.clone());
+            r = verifier.verify_round(poly, rng)?;
+        }
+        Ok(round_polynomials)
+    }
+
+    fn base_sum<B: Field>(factors: &[DenseMLE<B>]) -> B {
+        (0..factors[0].evaluations().len())
+            .map(|b| factors.iter().map(|f| f.evaluations()[b]).product::<B>())
+            .sum()
+    }
+
+    #[test]
+    fn babybear_product_with_quartic_challenges() {
+        let factors = random_table::<BabyBear>(5);
+        let claim = BabyBearExt4::from_base_prime_field(base_sum(&factors));
+        let round_polynomials = run_extension_product(factors.clone(), claim).unwrap();
+
+        // only the challenges move the round polynomials out of the base field
+        let in_base_field = |c: &BabyBearExt4| {
+            c.to_base_prime_field_elements()
+                .skip(1)
+                .all(|x| x == BabyBear::from(0))
+        };
+        assert!(round_polynomials[0].coeffs.iter().all(in_base_field));
+        assert!(!round_polynomials[1].coeffs.iter().all(in_base_field));
+
+        assert_eq!(
+            run_extension_product(factors, claim + BabyBearExt4::one()),
+            Err(SumcheckError::ClaimMismatch { round: 0 })
+        );
+    }
+
+    #[test]
+    fn mersenne31_product_with_cubic_challenges() {
+        let factors = random_table::<Mersenne31>(4);
+        let claim = Mersenne31Ext3::from_base_prime_field(base_sum(&factors));
+        run_extension_product(factors, claim).unwrap();
+    }
+
+    #[test]
+    fn oracle_matches_base_field_evaluation() {
+        let factors = random_table::<Goldilocks>(3);
+        let g = ExtensionMleProduct::<GoldilocksExt2>::new(factors.clone());
+        let point: Vec<Goldilocks> = (1..4u64).map(Goldilocks::from).collect();
+        let lifted: Vec<GoldilocksExt2> = point
+            .iter()
+            .map(|x| GoldilocksExt2::from_base_prime_field(*x))
+            .collect();
+        let expected: Goldilocks = factors.iter().map(|f| f.evaluate(&point)).product();
+        assert_eq!(
+            Oracle::evaluate(&g, &lifted),
+            Ok(GoldilocksExt2::from_base_prime_field(expected))
+        );
+    }
+
+    #[test]
+    fn lifted_sparse_polynomial() {
+        // 2 x_1^3 + x_1 x_3 + x_2 x_3 over Goldilocks, which sums to 12
+        let g = SparseMVPolynomial::from_coefficients_slice(
+            3,
+            &[
+                (Goldilocks::from(2), SparseTerm::new(vec![(0, 3)])),
+                (Goldilocks::from(1), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (Goldilocks::from(1), SparseTerm::new(vec![(1, 1), (2, 1)])),
+            ],
+        );
+        let lifted = lift_polynomial::<GoldilocksExt2>(&g);
+        let transcript =
+            run_sumcheck(lifted, GoldilocksExt2::from(12u64), &mut test_rng()).unwrap();
+        assert!(transcript
+            .challenges
+            .iter()
+            .all(|r| r.c1 != Goldilocks::from(0)));
+    }
+}
@@ -0,0 +1,144 @@
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+
+use super::SumcheckPolynomial;
+use crate::lagrange::{
+    multivariate::multivariate::DenseMLE,
+    univariate::univariate::{interpolate, node},
+};
+
+/// `g = f_1 * f_2 * ... * f_d` for multilinear `f_j` given by their evaluation tables.
+///
+/// This is the standard linear-time prover: round `i` reads each table once to compute `g_i` in
+/// O(d^2 * 2^(n-i)), then folds every table with the challenge `r_i`, halving its size. Summed over
+/// all rounds this is O(d^2 * 2^n) field operations, instead of re-enumerating the hypercube each round.
+#[derive(Debug, Clone)]
+pub struct MleProduct<F: Field> {
+    num_vars: usize,
+    factors: Vec<DenseMLE<F>>,
+}
+
+impl<F: Field> MleProduct<F> {
+    pub fn new(factors: Vec<DenseMLE<F>>) -> Self {
+        assert!(!factors.is_empty(), "product needs at least one factor");
+        let num_vars = factors[0].num_vars();
+        assert!(
+            factors.iter().all(|f| f.num_vars() == num_vars),
+            "all factors should have the same number of variables"
+        );
+        MleProduct { num_vars, factors }
+    }
+
+    pub fn factors(&self) -> &[DenseMLE<F>] {
+        &self.factors
+    }
+
+    /// The degree of `g` in each variable, i.e. the number of factors.
+    pub fn degree(&self) -> usize {
+        self.factors.len()
+    }
+
+    /// `g(point) = prod_j f_j(point)`
+    pub fn evaluate(&self, point: &[F]) -> F {
+        self.factors.iter().map(|f| f.evaluate(point)).product()
+    }
+}
+
+impl<F: Field> SumcheckPolynomial<F> for MleProduct<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn max_degree(&self) -> usize {
+        self.degree()
+    }
+
+    fn round_polynomial(&self, _round: usize, _challenges: &[F]) -> DensePolynomial<F> {
+        // the tables are already folded with every previous challenge, so the current variable is
+        // the most significant bit of what is left: f_j(X, b) = lo_j[b] + X * (hi_j[b] - lo_j[b])
+        let degree = self.degree();
+        let half = self.factors[0].evaluations().len() / 2;
+        let mut evals = vec![F::zero(); degree + 1];
+        let mut products = vec![F::one(); degree + 1];
+        let nodes: Vec<F> = (0..=degree).map(node).collect();
+        for b in 0..half {
+            products.iter_mut().for_each(|p| *p = F::one());
+            for f in &self.factors {
+                let lo = f.evaluations()[b];
+                let step = f.evaluations()[b + half] - lo;
+                // f_j(node(t), b) for t = 0..=degree
+                for (p, t) in products.iter_mut().zip(&nodes) {
+                    *p *= lo + *t * step;
+                }
+            }
+            for (eval, p) in evals.iter_mut().zip(&products) {
+                *eval += p;
+            }
+        }
+        interpolate(&evals)
+    }
+
+    fn fix_variable(&mut self, r: F) {
+        self.factors = self
+            .factors
+            .iter()
+            .map(|f| f.fix_first_variable(r))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{UniformRand, Zero};
+    use ark_poly::Polynomial;
+    use ark_std::{rand::Rng, test_rng};
+
+    use super::*;
+    use crate::sumcheck::test_utils::Fq;
+    use crate::sumcheck::{FnOracle, Prover, Verifier};
+
+    fn random_mle<R: Rng>(num_vars: usize, rng: &mut R) -> DenseMLE<Fq> {
+        DenseMLE::new(
+            num_vars,
+            (0..1 << num_vars).map(|_| Fq::rand(rng)).collect(),
+        )
+    }
+
+    fn brute_force_claim(g: &MleProduct<Fq>) -> Fq {
+        (0..1 << g.num_vars)
+            .map(|b| g.factors.iter().map(|f| f.evaluations()[b]).product::<Fq>())
+            .sum()
+    }
+
+    #[test]
+    fn first_round_polynomial_sums_to_claim() {
+        let rng = &mut test_rng();
+        let g = MleProduct::new((0..3).map(|_| random_mle(3, rng)).collect());
+        let g_1 = g.round_polynomial(0, &[]);
+        assert!(g_1.degree() <= 3);
+        assert_eq!(
+            g_1.evaluate(&Fq::zero()) + g_1.evaluate(&Fq::from(1)),
+            brute_force_claim(&g)
+        );
+    }
+
+    #[test]
+    fn protocol_with_mle_product() {
+        const V: usize = 4;
+        let rng = &mut test_rng();
+        let g = MleProduct::new((0..3).map(|_| random_mle(V, rng)).collect());
+        let claim = brute_force_claim(&g);
+
+        let oracle_g = g.clone();
+        let oracle = FnOracle(move |point: &[Fq]| Ok(oracle_g.evaluate(point)));
+        let info = g.info();
+        let mut prover: Prover<Fq, MleProduct<Fq>> = Prover::init(g).unwrap();
+        let mut verifier = Verifier::init(oracle, claim, info).unwrap();
+
+        let mut r = None;
+        for _ in 0..V {
+            r = verifier.verify_round(prover.prove_round(r), rng).unwrap();
+        }
+        assert!(verifier.subclaim().is_some());
+    }
+}
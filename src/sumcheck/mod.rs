@@ -1,35 +1,199 @@
 use ark_ff::Field;
 use ark_poly::{
     polynomial::multivariate::{SparsePolynomial as SparseMVPolynomial, SparseTerm},
+    univariate::DensePolynomial,
     DenseMVPolynomial, Polynomial,
 };
 use ark_std::rand::RngCore;
+use std::fmt;
 
-use self::util::util::reduce_poly_to_univar_at_x;
-mod util;
+#[cfg(feature = "rayon")]
+pub use self::util::util::get_claim_parallel;
+pub use self::util::util::{
+    curve, get_claim, get_claim_fast, get_claim_over_domain, get_claim_over_domain_fast, line,
+    restrict_mle_to_curve, restrict_mle_to_line, restrict_to_line,
+};
+use self::util::util::{
+    reduce_poly_to_univar_at_x, reduce_poly_to_univar_over_domain, to_univariate,
+};
+pub use batched::{BatchedProver, BatchedVerifier};
+pub use domain::SumDomain;
+pub use driver::{run_sumcheck, Step, Sumcheck, Transcript};
+pub use extension::{lift_polynomial, ExtensionMleProduct};
+pub use mle::MleProduct;
+pub use oracle::{FnOracle, OracleError, SubClaim};
+pub use reduction::{
+    reduce_on_curve, reduce_two_to_one, MultiClaimProver, MultiClaimVerifier, ReductionError,
+};
+pub use virtual_poly::VirtualPolynomial;
+pub mod batched;
+pub mod cheating;
+pub mod domain;
+pub mod driver;
+pub mod extension;
+pub mod mle;
+pub mod oracle;
+pub mod reduction;
+#[cfg(test)]
+pub(crate) mod test_utils;
+#[allow(clippy::module_inception)]
+pub(crate) mod util;
+pub mod virtual_poly;
+
+/// A polynomial the prover can run sumcheck on. The prover asks it for the round polynomial `g_i`
+/// and then tells it the verifier's challenge `r_i`, so representations that keep state between rounds
+/// (like the folded tables of `MleProduct`) can update it in place.
+pub trait SumcheckPolynomial<F: Field> {
+    fn num_vars(&self) -> usize;
+
+    /// The largest degree of `g` in any single variable, which bounds the degree of every `g_i`.
+    fn max_degree(&self) -> usize;
+
+    /// Check that the variables `g` actually uses agree with `num_vars`.
+    fn check_num_vars(&self) -> Result<(), SumcheckError> {
+        Ok(())
+    }
+
+    /// What the verifier needs to know about `g` to check the rounds.
+    fn info(&self) -> PolynomialInfo {
+        PolynomialInfo {
+            num_vars: self.num_vars(),
+            max_degree: self.max_degree(),
+        }
+    }
+
+    /// The univariate `g_i(X) = sum_{b in {0,1}^(n-i-1)} g(r_1, ..., r_{i-1}, X, b)` for `i = round`.
+    fn round_polynomial(&self, round: usize, challenges: &[F]) -> DensePolynomial<F>;
 
-pub struct Prover<F: Field, const N: usize> {
-    g: SparseMVPolynomial<F, SparseTerm>,
+    /// Bind the variable of the current round to the verifier's challenge.
+    fn fix_variable(&mut self, r: F);
+
+    /// Whether `g` can be summed over `H^n` for this `H`. Table-based representations only
+    /// know `g` on the boolean hypercube, so that is all they support by default.
+    fn supports_domain(&self, domain: &SumDomain<F>) -> bool {
+        domain.is_boolean()
+    }
+
+    /// `g_i` with the remaining variables summed over `H` instead of {0,1}.
+    /// Only called when `supports_domain` is true.
+    fn round_polynomial_over_domain(
+        &self,
+        round: usize,
+        challenges: &[F],
+        _domain: &SumDomain<F>,
+    ) -> DensePolynomial<F> {
+        self.round_polynomial(round, challenges)
+    }
+}
+
+impl<F: Field> SumcheckPolynomial<F> for SparseMVPolynomial<F, SparseTerm> {
+    fn num_vars(&self) -> usize {
+        DenseMVPolynomial::num_vars(self)
+    }
+
+    fn max_degree(&self) -> usize {
+        self.terms()
+            .iter()
+            .flat_map(|(_, term)| term.iter().map(|&(_, power)| power))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn check_num_vars(&self) -> Result<(), SumcheckError> {
+        // `SparsePolynomial`'s fields are public, so terms can mention variables past `num_vars`
+        let actual = self
+            .terms()
+            .iter()
+            .flat_map(|(_, term)| term.iter().map(|&(var, _)| var + 1))
+            .max()
+            .unwrap_or(0);
+        if actual > DenseMVPolynomial::num_vars(self) {
+            return Err(SumcheckError::NumVarsMismatch {
+                declared: DenseMVPolynomial::num_vars(self),
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    fn round_polynomial(&self, round: usize, challenges: &[F]) -> DensePolynomial<F> {
+        to_univariate(&reduce_poly_to_univar_at_x(
+            self.clone(),
+            round,
+            challenges.to_vec(),
+        ))
+    }
+
+    // the sparse representation re-evaluates from the original `g` every round, so there is nothing to update
+    fn fix_variable(&mut self, _r: F) {}
+
+    fn supports_domain(&self, _domain: &SumDomain<F>) -> bool {
+        true
+    }
+
+    fn round_polynomial_over_domain(
+        &self,
+        round: usize,
+        challenges: &[F],
+        domain: &SumDomain<F>,
+    ) -> DensePolynomial<F> {
+        to_univariate(&reduce_poly_to_univar_over_domain(
+            self.clone(),
+            round,
+            challenges.to_vec(),
+            domain,
+        ))
+    }
+}
+
+/// The public shape of `g`: the number of rounds and the degree bound on each round polynomial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolynomialInfo {
+    pub num_vars: usize,
+    pub max_degree: usize,
+}
+
+pub struct Prover<F: Field, P: SumcheckPolynomial<F> = SparseMVPolynomial<F, SparseTerm>> {
+    g: P,
+    domain: SumDomain<F>,
     round: usize,
     // all the challenges `r_i` from the verifier
     challenges: Vec<F>,
 }
 
-impl<F: Field, const N: usize> Prover<F, N> {
-    pub fn init(g: SparseMVPolynomial<F, SparseTerm>) -> Self {
-        Prover {
+impl<F: Field, P: SumcheckPolynomial<F>> Prover<F, P> {
+    /// The number of rounds is taken from `g`, which fails if `g` uses more variables than it declares.
+    pub fn init(g: P) -> Result<Self, SumcheckError> {
+        g.check_num_vars()?;
+        Ok(Prover {
             g,
+            domain: SumDomain::boolean(),
             round: 0,
             challenges: vec![],
+        })
+    }
+
+    /// Sum over `H^n` instead of {0,1}^n. Fails if `g`'s representation can't be evaluated on `H`.
+    pub fn with_domain(mut self, domain: SumDomain<F>) -> Result<Self, SumcheckError> {
+        assert_eq!(self.round, 0, "domain should be set before the first round");
+        if !self.g.supports_domain(&domain) {
+            return Err(SumcheckError::UnsupportedDomain);
         }
+        self.domain = domain;
+        Ok(self)
     }
 
-    pub fn prove_round(&mut self, r: Option<F>) -> SparseMVPolynomial<F, SparseTerm> {
+    pub fn num_vars(&self) -> usize {
+        self.g.num_vars()
+    }
+
+    pub fn prove_round(&mut self, r: Option<F>) -> DensePolynomial<F> {
         // partially evaluate new polynomials with g and the challenge for each round
-        // TODO: use refs, not clone; throw errors instead of assertions (since this is library code?)
+        // TODO: throw errors instead of assertions (since this is library code?)
         match r {
             Some(challenge) => {
                 self.challenges.push(challenge);
+                self.g.fix_variable(challenge);
             }
             None => {
                 assert!(
@@ -38,116 +202,215 @@ impl<F: Field, const N: usize> Prover<F, N> {
                 );
             }
         }
-        let poly_i =
-            reduce_poly_to_univar_at_x::<F, N>(self.g.clone(), self.round, self.challenges.clone());
+        let poly_i = if self.domain.is_boolean() {
+            self.g.round_polynomial(self.round, &self.challenges)
+        } else {
+            self.g
+                .round_polynomial_over_domain(self.round, &self.challenges, &self.domain)
+        };
         // update the round as the last step
         self.round += 1;
         poly_i
     }
 }
-pub struct Verifier<F: Field, const N: usize> {
-    g: SparseMVPolynomial<F, SparseTerm>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SumcheckError {
+    /// `sum_{h in H} g_i(h)` did not match the claim carried over from the previous round.
+    ClaimMismatch {
+        round: usize,
+    },
+    /// The round polynomial has a higher degree than `g` allows in a single variable.
+    DegreeTooHigh {
+        round: usize,
+        degree: usize,
+        max_degree: usize,
+    },
+    /// `g_v(r_v)` did not match the oracle's evaluation of `g(r_1, ..., r_v)`.
+    FinalCheckFailed,
+    /// All rounds have already been verified.
+    ProtocolFinished,
+    /// The result of the protocol was requested before all rounds were verified.
+    ProtocolNotFinished,
+    /// The polynomial's representation can't be summed over the requested domain.
+    UnsupportedDomain,
+    /// The number of variables declared for `g` disagrees with the variables it actually has.
+    NumVarsMismatch {
+        declared: usize,
+        actual: usize,
+    },
+    /// A cheating prover's guesses can't hide its debt: there are none, or `L(0) + L(1) = 0`.
+    InvalidGuesses,
+    Oracle(OracleError),
+}
+
+impl fmt::Display for SumcheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SumcheckError::ClaimMismatch { round } => {
+                write!(f, "round {} polynomial does not match the claim", round)
+            }
+            SumcheckError::DegreeTooHigh {
+                round,
+                degree,
+                max_degree,
+            } => write!(
+                f,
+                "round {} polynomial has degree {}, expected at most {}",
+                round, degree, max_degree
+            ),
+            SumcheckError::FinalCheckFailed => write!(f, "final oracle check failed"),
+            SumcheckError::ProtocolFinished => write!(f, "all rounds have been verified"),
+            SumcheckError::ProtocolNotFinished => write!(f, "not all rounds have been verified"),
+            SumcheckError::UnsupportedDomain => {
+                write!(f, "polynomial can't be summed over this domain")
+            }
+            SumcheckError::NumVarsMismatch { declared, actual } => write!(
+                f,
+                "polynomial declares {} variables but has {}",
+                declared, actual
+            ),
+            SumcheckError::InvalidGuesses => {
+                write!(f, "guesses must be nonempty with L(0) + L(1) nonzero")
+            }
+            SumcheckError::Oracle(e) => write!(f, "oracle error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SumcheckError {}
+
+impl From<OracleError> for SumcheckError {
+    fn from(e: OracleError) -> Self {
+        SumcheckError::Oracle(e)
+    }
+}
+
+pub struct Verifier<F: Field, O: oracle::Oracle<F> = SparseMVPolynomial<F, SparseTerm>> {
+    // `None` when the final query is deferred to the caller through `subclaim`
+    oracle: Option<O>,
+    info: PolynomialInfo,
+    domain: SumDomain<F>,
     round: usize,
     challenges: Vec<F>,
-    previous_poly: Option<SparseMVPolynomial<F, SparseTerm>>,
+    previous_poly: Option<DensePolynomial<F>>,
     claim: F,
+    subclaim: Option<SubClaim<F>>,
 }
 
-impl<F: Field, const N: usize> Verifier<F, N> {
-    pub fn init(g: SparseMVPolynomial<F, SparseTerm>, initial_claim: F) -> Self {
+impl<F: Field, O: oracle::Oracle<F>> Verifier<F, O> {
+    /// Fails if the oracle knows its number of variables and it disagrees with `info`.
+    pub fn init(oracle: O, initial_claim: F, info: PolynomialInfo) -> Result<Self, SumcheckError> {
+        if let Some(actual) = oracle.num_vars() {
+            if actual != info.num_vars {
+                return Err(SumcheckError::NumVarsMismatch {
+                    declared: info.num_vars,
+                    actual,
+                });
+            }
+        }
+        Ok(Verifier {
+            oracle: Some(oracle),
+            info,
+            domain: SumDomain::boolean(),
+            round: 0,
+            challenges: vec![],
+            previous_poly: None,
+            claim: initial_claim,
+            subclaim: None,
+        })
+    }
+
+    /// Run the rounds without access to `g`. Instead of querying an oracle after the last round,
+    /// the verifier leaves a `SubClaim` that the caller has to discharge.
+    pub fn init_deferred(initial_claim: F, info: PolynomialInfo) -> Self {
         Verifier {
-            g,
+            oracle: None,
+            info,
+            domain: SumDomain::boolean(),
             round: 0,
             challenges: vec![],
             previous_poly: None,
             claim: initial_claim,
+            subclaim: None,
         }
     }
 
-    fn query_oracle(&mut self) -> F {
-        self.g.evaluate(&self.challenges)
+    /// Check the claim `sum_{x in H^n} g(x) = c` instead of the sum over {0,1}^n.
+    pub fn with_domain(mut self, domain: SumDomain<F>) -> Self {
+        assert_eq!(self.round, 0, "domain should be set before the first round");
+        self.domain = domain;
+        self
+    }
+
+    /// The claim `g(r_1, ..., r_n) = g_n(r_n)`, available once every round has been verified.
+    pub fn subclaim(&self) -> Option<&SubClaim<F>> {
+        self.subclaim.as_ref()
     }
 
     pub fn verify_round(
         &mut self,
-        current_poly: SparseMVPolynomial<F, SparseTerm>,
+        current_poly: DensePolynomial<F>,
         rng: &mut dyn RngCore,
-    ) -> Option<F> {
-        // since our polynomials are univariate only in theory (in practice it's represented as a multivariate polynomial),
-        // to evaluate it at any variable X, we need to evaluate the whole polynomial at [X, X, ...]
-
-        // if first round, don't use prev poly, just eval at 0 and 1, assert its equal to claim, then return our first challenge element
-        assert!(
-            current_poly.num_vars() == 1,
-            "polynomial should be univariate"
-        );
-        let computed_0 = current_poly.evaluate(&vec![F::zero(); N]);
-        let computed_1 = current_poly.evaluate(&vec![F::one(); N]);
-
-        let computed = computed_0 + computed_1;
-
-        if self.round == 0 {
-            assert_eq!(computed, self.claim, "polynomials should be equal");
-        } else {
-            match &self.previous_poly {
-                Some(prev_poly) => {
-                    assert_eq!(
-                        computed,
-                        prev_poly.evaluate(&vec![*self.challenges.last().unwrap(); N]),
-                        "polynomials should be equal"
-                    );
-                }
-                None => {
-                    panic!("previous poly should have been set by now");
-                }
-            }
+    ) -> Result<Option<F>, SumcheckError> {
+        // if first round, don't use prev poly, just sum over the domain, check its equal to claim, then return our first challenge element
+        if self.round == self.info.num_vars {
+            return Err(SumcheckError::ProtocolFinished);
+        }
+        if current_poly.degree() > self.info.max_degree {
+            return Err(SumcheckError::DegreeTooHigh {
+                round: self.round,
+                degree: current_poly.degree(),
+                max_degree: self.info.max_degree,
+            });
+        }
+        let computed = self.domain.sum(&current_poly);
+
+        let expected = match &self.previous_poly {
+            Some(prev_poly) => prev_poly.evaluate(self.challenges.last().unwrap()),
+            None => self.claim,
+        };
+        if computed != expected {
+            return Err(SumcheckError::ClaimMismatch { round: self.round });
         }
 
         let r: F = F::rand(rng);
         self.challenges.push(r);
 
         // final check that g(r_1, r_2, ..., r_n) = g_v(r_n)
-        if self.round == N - 1 {
-            assert_eq!(
-                current_poly.evaluate(&vec![*self.challenges.last().unwrap(); N]),
-                self.query_oracle(),
-                "Final round should be equal"
-            )
+        if self.round == self.info.num_vars - 1 {
+            let subclaim = SubClaim {
+                point: self.challenges.clone(),
+                expected_value: current_poly.evaluate(&r),
+            };
+            if let Some(oracle) = &self.oracle {
+                if !subclaim.check(oracle)? {
+                    return Err(SumcheckError::FinalCheckFailed);
+                }
+            }
+            self.subclaim = Some(subclaim);
         }
         self.round += 1;
         self.previous_poly = Some(current_poly);
-        Some(r)
+        Ok(Some(r))
     }
 }
+#[cfg(test)]
 #[allow(unused_imports, dead_code)]
 mod test {
     use std::vec;
 
-    use ark_ff::{Fp64, MontBackend, MontConfig, One, Zero};
+    use ark_ff::{One, Zero};
     use ark_poly::{multivariate::Term, DenseMVPolynomial, Polynomial};
 
     use super::*;
-    use crate::sumcheck::util::util::{get_claim, reduce_poly_to_univar_at_x};
+    use crate::lagrange::multivariate::multivariate::DenseMLE;
+    use crate::sumcheck::test_utils::{sample_poly, Fq};
+    use crate::sumcheck::util::util::{
+        get_claim, get_claim_over_domain, reduce_poly_to_univar_at_x,
+    };
     use ark_ff::UniformRand;
     use ark_std::test_rng;
 
-    #[derive(MontConfig)]
-    #[modulus = "71"]
-    #[generator = "3"]
-    pub struct FqConfig;
-    pub type Fq = Fp64<MontBackend<FqConfig, 1>>;
-
-    fn sample_poly() -> SparseMVPolynomial<Fq, SparseTerm> {
-        SparseMVPolynomial::from_coefficients_slice(
-            3,
-            &[
-                (Fq::from(2), SparseTerm::new(vec![(0, 3)])),
-                (Fq::from(1), SparseTerm::new(vec![(0, 1), (2, 1)])),
-                (Fq::from(1), SparseTerm::new(vec![(1, 1), (2, 1)])),
-            ],
-        )
-    }
-
     #[test]
     fn test_g() {
         let h: Fq = sample_poly().evaluate(&vec![Fq::zero(), Fq::zero(), Fq::zero()]);
@@ -165,7 +428,7 @@ mod test {
 
         // eg prover's polynomial in first round has no challenge elements
         // whats the diff between Fq and Fp again?
-        let reduced_polynomial = reduce_poly_to_univar_at_x::<Fq, 3>(g, 0, vec![]);
+        let reduced_polynomial = reduce_poly_to_univar_at_x(g, 0, vec![]);
 
         let expected_poly: SparseMVPolynomial<Fq, SparseTerm> =
             DenseMVPolynomial::from_coefficients_slice(
@@ -187,7 +450,7 @@ mod test {
 
         // eg prover's polynomial in first round has no challenge elements
         // whats the diff between Fq and Fp again?
-        let reduced_polynomial = reduce_poly_to_univar_at_x::<Fq, 3>(g, 1, vec![Fq::from(2u8)]);
+        let reduced_polynomial = reduce_poly_to_univar_at_x(g, 1, vec![Fq::from(2u8)]);
 
         let expected_poly: SparseMVPolynomial<Fq, SparseTerm> =
             DenseMVPolynomial::from_coefficients_slice(
@@ -212,7 +475,7 @@ mod test {
         // eg prover's polynomial in first round has no challenge elements
         // whats the diff between Fq and Fp again?
         let reduced_polynomial =
-            reduce_poly_to_univar_at_x::<Fq, 3>(g, 2, vec![Fq::from(2u8), Fq::from(3u8)]);
+            reduce_poly_to_univar_at_x(g, 2, vec![Fq::from(2u8), Fq::from(3u8)]);
 
         let expected_poly: SparseMVPolynomial<Fq, SparseTerm> =
             DenseMVPolynomial::from_coefficients_slice(
@@ -232,13 +495,12 @@ mod test {
     // This test is a from the example Sum Check in "Proofs, Arguments, and Zero-Knowledge"
     #[test]
     fn test_protocol() {
-        const V: usize = 3usize;
         let g = sample_poly();
         let rng = &mut test_rng();
-        let claim = get_claim::<Fq, V>(g.clone());
+        let claim = get_claim(g.clone());
 
-        let mut prover: Prover<Fq, V> = Prover::init(g.clone());
-        let mut verifier: Verifier<Fq, V> = Verifier::init(g, claim);
+        let mut prover: Prover<Fq> = Prover::init(g.clone()).unwrap();
+        let mut verifier: Verifier<Fq> = Verifier::init(g.clone(), claim, g.info()).unwrap();
 
         assert_eq!(claim, Fq::from(12));
         let mut r: Option<Fq> = None;
@@ -246,16 +508,218 @@ mod test {
         // the following two vectors are just for debugging purposes.
         // In reality, the verifier and prover both store all the challenges,
         // and the verifier stores the previous polynomial they received from the prover.
-        let mut polynomials: Vec<SparseMVPolynomial<Fq, SparseTerm>> = vec![];
+        let mut polynomials: Vec<DensePolynomial<Fq>> = vec![];
         let mut challenges: Vec<Option<Fq>> = vec![];
         challenges.push(r);
 
-        let mut poly_i: SparseMVPolynomial<Fq, SparseTerm>;
-        for _ in 0..V {
+        let mut poly_i: DensePolynomial<Fq>;
+        for _ in 0..prover.num_vars() {
             poly_i = prover.prove_round(r);
             polynomials.push(poly_i.clone());
-            r = verifier.verify_round(poly_i, rng);
+            r = verifier.verify_round(poly_i, rng).unwrap();
             challenges.push(r);
         }
     }
+
+    fn run_rounds<O: oracle::Oracle<Fq>>(
+        g: SparseMVPolynomial<Fq, SparseTerm>,
+        verifier: &mut Verifier<Fq, O>,
+    ) -> Result<(), SumcheckError> {
+        let rng = &mut test_rng();
+        let mut prover: Prover<Fq> = Prover::init(g)?;
+        let mut r: Option<Fq> = None;
+        for _ in 0..prover.num_vars() {
+            r = verifier.verify_round(prover.prove_round(r), rng)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_protocol_with_mle_oracle() {
+        // g(x_1, x_2) = 3 + 2 x_1 + 4 x_1 x_2, whose table over {0,1}^2 is [3, 3, 5, 9]
+        let g = SparseMVPolynomial::from_coefficients_slice(
+            2,
+            &[
+                (Fq::from(3), SparseTerm::new(vec![])),
+                (Fq::from(2), SparseTerm::new(vec![(0, 1)])),
+                (Fq::from(4), SparseTerm::new(vec![(0, 1), (1, 1)])),
+            ],
+        );
+        let table = DenseMLE::new(2, vec![Fq::from(3), Fq::from(3), Fq::from(5), Fq::from(9)]);
+        let mut verifier: Verifier<Fq, DenseMLE<Fq>> =
+            Verifier::init(table, Fq::from(20), g.info()).unwrap();
+        run_rounds(g, &mut verifier).unwrap();
+        assert!(verifier.subclaim().is_some());
+    }
+
+    #[test]
+    fn test_protocol_with_closure_oracle() {
+        let g = sample_poly();
+        let claim = get_claim(g.clone());
+        let oracle_g = g.clone();
+        let oracle = FnOracle(move |point: &[Fq]| oracle::Oracle::evaluate(&oracle_g, point));
+        let mut verifier = Verifier::init(oracle, claim, g.info()).unwrap();
+        run_rounds(g.clone(), &mut verifier).unwrap();
+
+        let lying_oracle = FnOracle(|_: &[Fq]| Ok(Fq::zero()));
+        let mut verifier = Verifier::init(lying_oracle, claim, g.info()).unwrap();
+        assert_eq!(
+            run_rounds(sample_poly(), &mut verifier),
+            Err(SumcheckError::FinalCheckFailed)
+        );
+    }
+
+    #[test]
+    fn test_protocol_deferred_subclaim() {
+        let g = sample_poly();
+        let claim = get_claim(g.clone());
+        let mut verifier: Verifier<Fq> = Verifier::init_deferred(claim, g.info());
+        run_rounds(g.clone(), &mut verifier).unwrap();
+
+        let subclaim = verifier.subclaim().unwrap();
+        assert_eq!(subclaim.point.len(), 3);
+        assert!(subclaim.check(&g).unwrap());
+    }
+
+    #[test]
+    fn test_wrong_claim_rejected() {
+        let g = sample_poly();
+        let claim = get_claim(g.clone());
+        let mut verifier: Verifier<Fq> =
+            Verifier::init(g.clone(), claim + Fq::one(), g.info()).unwrap();
+        assert_eq!(
+            run_rounds(g, &mut verifier),
+            Err(SumcheckError::ClaimMismatch { round: 0 })
+        );
+    }
+
+    #[test]
+    fn test_degree_bound() {
+        let g = sample_poly();
+        assert_eq!(SumcheckPolynomial::max_degree(&g), 3);
+
+        let claim = get_claim(g.clone());
+        let info = PolynomialInfo {
+            num_vars: 3,
+            max_degree: 2,
+        };
+        let mut verifier: Verifier<Fq> = Verifier::init(g.clone(), claim, info).unwrap();
+        assert_eq!(
+            run_rounds(g, &mut verifier),
+            Err(SumcheckError::DegreeTooHigh {
+                round: 0,
+                degree: 3,
+                max_degree: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_num_vars_mismatch() {
+        // terms mention x_3, but the polynomial only declares two variables
+        let g = SparseMVPolynomial {
+            num_vars: 2,
+            terms: vec![(Fq::one(), SparseTerm::new(vec![(2, 1)]))],
+        };
+        assert_eq!(
+            Prover::init(g).err(),
+            Some(SumcheckError::NumVarsMismatch {
+                declared: 2,
+                actual: 3
+            })
+        );
+
+        let g = sample_poly();
+        let info = PolynomialInfo {
+            num_vars: 4,
+            max_degree: 3,
+        };
+        assert_eq!(
+            Verifier::init(g, Fq::zero(), info).err(),
+            Some(SumcheckError::NumVarsMismatch {
+                declared: 4,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_protocol_over_larger_domain() {
+        let g = sample_poly();
+        let rng = &mut test_rng();
+        let domain = SumDomain::range(3);
+        let claim = get_claim_over_domain(g.clone(), &domain);
+        assert_ne!(claim, get_claim(g.clone()));
+
+        let mut prover = Prover::init(g.clone())
+            .unwrap()
+            .with_domain(domain.clone())
+            .unwrap();
+        let mut verifier: Verifier<Fq> = Verifier::init(g.clone(), claim, g.info())
+            .unwrap()
+            .with_domain(domain.clone());
+        let mut r = None;
+        for _ in 0..prover.num_vars() {
+            r = verifier.verify_round(prover.prove_round(r), rng).unwrap();
+        }
+
+        // a prover summing over {0,1} doesn't convince a verifier summing over {0,1,2}
+        let mut verifier: Verifier<Fq> = Verifier::init(g.clone(), claim, g.info())
+            .unwrap()
+            .with_domain(domain);
+        let mut prover: Prover<Fq> = Prover::init(g).unwrap();
+        assert_eq!(
+            verifier.verify_round(prover.prove_round(None), rng),
+            Err(SumcheckError::ClaimMismatch { round: 0 })
+        );
+    }
+
+    #[test]
+    fn test_fast_claim_matches_brute_force() {
+        let g = sample_poly();
+        assert_eq!(get_claim_fast(&g), get_claim(g.clone()));
+
+        let domain = SumDomain::range(3);
+        assert_eq!(
+            get_claim_over_domain_fast(&g, &domain),
+            get_claim_over_domain(g.clone(), &domain)
+        );
+
+        // a polynomial that doesn't depend on every variable, with a constant term
+        let g = SparseMVPolynomial::from_coefficients_slice(
+            4,
+            &[
+                (Fq::from(5), SparseTerm::new(vec![(1, 2)])),
+                (Fq::from(3), SparseTerm::new(vec![])),
+            ],
+        );
+        assert_eq!(get_claim_fast(&g), get_claim(g.clone()));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_claim_matches_brute_force() {
+        let rng = &mut test_rng();
+        let terms: Vec<(Fq, SparseTerm)> = (0..12)
+            .map(|i| {
+                (
+                    Fq::rand(rng),
+                    SparseTerm::new(vec![(i, 1), ((i + 5) % 12, 2)]),
+                )
+            })
+            .collect();
+        let g = SparseMVPolynomial::from_coefficients_vec(12, terms);
+        assert_eq!(get_claim_parallel(&g), get_claim_fast(&g));
+        assert_eq!(get_claim_parallel(&sample_poly()), Fq::from(12));
+    }
+
+    #[test]
+    fn test_table_prover_rejects_non_boolean_domain() {
+        let table = DenseMLE::new(1, vec![Fq::from(3), Fq::from(5)]);
+        let prover = Prover::init(MleProduct::new(vec![table])).unwrap();
+        assert_eq!(
+            prover.with_domain(SumDomain::range(3)).err(),
+            Some(SumcheckError::UnsupportedDomain)
+        );
+    }
 }
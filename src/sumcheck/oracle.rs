@@ -0,0 +1,124 @@
+use std::fmt;
+
+use ark_ff::Field;
+use ark_poly::{
+    polynomial::multivariate::{SparsePolynomial as SparseMVPolynomial, SparseTerm},
+    univariate::DensePolynomial,
+    DenseMVPolynomial, Polynomial,
+};
+
+use crate::lagrange::multivariate::multivariate::DenseMLE;
+
+/// Oracle access to the polynomial `g` that the verifier queries exactly once, at the end of the protocol.
+/// The verifier never needs `g` itself, only a way to learn `g(r_1, ..., r_n)`.
+pub trait Oracle<F: Field> {
+    fn evaluate(&self, point: &[F]) -> Result<F, OracleError>;
+
+    /// The number of variables of the polynomial behind the oracle, if the oracle knows it.
+    fn num_vars(&self) -> Option<usize> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OracleError {
+    /// The queried point does not have one coordinate per variable.
+    InvalidPoint { expected: usize, got: usize },
+    /// The oracle could not produce an evaluation, e.g. a failed opening.
+    Unavailable(String),
+}
+
+impl fmt::Display for OracleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OracleError::InvalidPoint { expected, got } => {
+                write!(
+                    f,
+                    "expected a point with {} coordinates, got {}",
+                    expected, got
+                )
+            }
+            OracleError::Unavailable(reason) => write!(f, "oracle unavailable: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for OracleError {}
+
+/// The full polynomial: only useful for testing, since a verifier holding `g` could compute the sum itself.
+impl<F: Field> Oracle<F> for SparseMVPolynomial<F, SparseTerm> {
+    fn evaluate(&self, point: &[F]) -> Result<F, OracleError> {
+        if point.len() != DenseMVPolynomial::num_vars(self) {
+            return Err(OracleError::InvalidPoint {
+                expected: DenseMVPolynomial::num_vars(self),
+                got: point.len(),
+            });
+        }
+        Ok(Polynomial::evaluate(self, &point.to_vec()))
+    }
+
+    fn num_vars(&self) -> Option<usize> {
+        Some(DenseMVPolynomial::num_vars(self))
+    }
+}
+
+/// A multilinear `g` given by its table of evaluations over the hypercube.
+impl<F: Field> Oracle<F> for DenseMLE<F> {
+    fn evaluate(&self, point: &[F]) -> Result<F, OracleError> {
+        if point.len() != self.num_vars() {
+            return Err(OracleError::InvalidPoint {
+                expected: self.num_vars(),
+                got: point.len(),
+            });
+        }
+        Ok(DenseMLE::evaluate(self, point))
+    }
+
+    fn num_vars(&self) -> Option<usize> {
+        Some(DenseMLE::num_vars(self))
+    }
+}
+
+/// A univariate polynomial, queried at a single point.
+impl<F: Field> Oracle<F> for DensePolynomial<F> {
+    fn evaluate(&self, point: &[F]) -> Result<F, OracleError> {
+        match point {
+            [x] => Ok(Polynomial::evaluate(self, x)),
+            _ => Err(OracleError::InvalidPoint {
+                expected: 1,
+                got: point.len(),
+            }),
+        }
+    }
+
+    fn num_vars(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// Wraps any closure as an oracle, e.g. one that opens a polynomial commitment.
+pub struct FnOracle<T>(pub T);
+
+impl<F: Field, T> Oracle<F> for FnOracle<T>
+where
+    T: Fn(&[F]) -> Result<F, OracleError>,
+{
+    fn evaluate(&self, point: &[F]) -> Result<F, OracleError> {
+        (self.0)(point)
+    }
+}
+
+/// The claim `g(point) = expected_value` that is left over once all rounds have been verified.
+/// Returned to the caller when the verifier runs without an oracle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubClaim<F: Field> {
+    pub point: Vec<F>,
+    pub expected_value: F,
+}
+
+impl<F: Field> SubClaim<F> {
+    /// Discharge the subclaim with a single oracle query.
+    pub fn check<O: Oracle<F>>(&self, oracle: &O) -> Result<bool, OracleError> {
+        Ok(oracle.evaluate(&self.point)? == self.expected_value)
+    }
+}
@@ -0,0 +1,428 @@
+use std::fmt;
+
+use ark_ff::Field;
+use ark_poly::{univariate::DensePolynomial, Polynomial};
+use ark_std::rand::RngCore;
+
+use super::{
+    util::util::curve, MleProduct, PolynomialInfo, Prover, SubClaim, SumcheckError, Verifier,
+};
+use crate::lagrange::multivariate::multivariate::{eq, DenseMLE};
+use crate::lagrange::univariate::univariate::node;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReductionError {
+    /// The prover's polynomial has a higher degree than the restriction can have.
+    DegreeTooHigh { degree: usize, max_degree: usize },
+    /// The prover's polynomial disagrees with claim `index`.
+    ClaimMismatch { index: usize },
+}
+
+impl fmt::Display for ReductionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReductionError::DegreeTooHigh { degree, max_degree } => write!(
+                f,
+                "polynomial has degree {}, expected at most {}",
+                degree, max_degree
+            ),
+            ReductionError::ClaimMismatch { index } => {
+                write!(f, "polynomial does not match claim {}", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReductionError {}
+
+/// Reduce the claims `W(b) = v_0` and `W(c) = v_1` to a single claim about `W`.
+///
+/// The prover sends `q(t) = W(l(t))` for the line `l` with `l(0) = b` and `l(1) = c`, computed with
+/// `restrict_to_line` or `restrict_mle_to_line`. The verifier checks `q(0) = v_0` and `q(1) = v_1`,
+/// picks a random `r` and is left with `W(l(r)) = q(r)`. If either claim is false, so is `q`, and
+/// the new claim holds with probability at most `max_degree / |F|`. `max_degree` bounds the total
+/// degree of `W`, which is its number of variables for an MLE.
+pub fn reduce_two_to_one<F: Field>(
+    first: &SubClaim<F>,
+    second: &SubClaim<F>,
+    q: &DensePolynomial<F>,
+    max_degree: usize,
+    rng: &mut dyn RngCore,
+) -> Result<SubClaim<F>, ReductionError> {
+    reduce_on_curve(&[first.clone(), second.clone()], q, max_degree, rng)
+}
+
+/// `reduce_two_to_one` for `k` claims `W(z_j) = v_j`, with the curve `gamma` of degree `k - 1` through
+/// `gamma(node(j)) = z_j` in place of the line. The prover sends `q(t) = W(gamma(t))`, e.g. from
+/// `restrict_mle_to_curve`, which has degree at most `(k - 1) * max_degree`, so a false claim survives
+/// with probability at most `(k - 1) * max_degree / |F|`.
+pub fn reduce_on_curve<F: Field>(
+    claims: &[SubClaim<F>],
+    q: &DensePolynomial<F>,
+    max_degree: usize,
+    rng: &mut dyn RngCore,
+) -> Result<SubClaim<F>, ReductionError> {
+    assert!(!claims.is_empty(), "there should be a claim to reduce");
+    let max_degree = (claims.len() - 1) * max_degree;
+    if q.degree() > max_degree {
+        return Err(ReductionError::DegreeTooHigh {
+            degree: q.degree(),
+            max_degree,
+        });
+    }
+    for (index, claim) in claims.iter().enumerate() {
+        if q.evaluate(&node(index)) != claim.expected_value {
+            return Err(ReductionError::ClaimMismatch { index });
+        }
+    }
+    let r = F::rand(rng);
+    let points: Vec<Vec<F>> = claims.iter().map(|claim| claim.point.clone()).collect();
+    Ok(SubClaim {
+        point: curve(&points, r),
+        expected_value: q.evaluate(&r),
+    })
+}
+
+/// Reduces `f(z_j) = v_j` for an MLE `f` to one claim by running sumcheck on
+/// `sum_x (sum_j rho_j * eq(z_j, x)) * f(x) = sum_j rho_j * v_j` for random `rho_j`.
+///
+/// Unlike `reduce_on_curve`, the prover never evaluates `f` outside the hypercube and the sumcheck
+/// only has degree 2, whatever the number of claims. A false claim survives the combination with
+/// probability `1 / |F|` and the sumcheck with `2 n / |F|`.
+pub struct MultiClaimProver<F: Field> {
+    prover: Prover<F, MleProduct<F>>,
+}
+
+impl<F: Field> MultiClaimProver<F> {
+    /// `points` are the `z_j` and `coefficients` the verifier's `rho_j`, see `MultiClaimVerifier::coefficients`.
+    pub fn init(
+        f: &DenseMLE<F>,
+        points: &[Vec<F>],
+        coefficients: &[F],
+    ) -> Result<Self, SumcheckError> {
+        if let Some(z) = points.iter().find(|z| z.len() != f.num_vars()) {
+            return Err(SumcheckError::NumVarsMismatch {
+                declared: f.num_vars(),
+                actual: z.len(),
+            });
+        }
+        let eq = DenseMLE::combined_eq_table(points, coefficients);
+        Ok(MultiClaimProver {
+            prover: Prover::init(MleProduct::new(vec![eq, f.clone()]))?,
+        })
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.prover.num_vars()
+    }
+
+    pub fn prove_round(&mut self, r: Option<F>) -> DensePolynomial<F> {
+        self.prover.prove_round(r)
+    }
+}
+
+pub struct MultiClaimVerifier<F: Field> {
+    verifier: Verifier<F>,
+    points: Vec<Vec<F>>,
+    coefficients: Vec<F>,
+}
+
+impl<F: Field> MultiClaimVerifier<F> {
+    /// Fails if the claims' points don't all have the same number of variables.
+    pub fn init(claims: &[SubClaim<F>], rng: &mut dyn RngCore) -> Result<Self, SumcheckError> {
+        assert!(!claims.is_empty(), "there should be a claim to reduce");
+        let num_vars = claims[0].point.len();
+        if let Some(claim) = claims.iter().find(|claim| claim.point.len() != num_vars) {
+            return Err(SumcheckError::NumVarsMismatch {
+                declared: num_vars,
+                actual: claim.point.len(),
+            });
+        }
+        let coefficients: Vec<F> = claims.iter().map(|_| F::rand(rng)).collect();
+        let claim = claims
+            .iter()
+            .zip(&coefficients)
+            .map(|(claim, rho)| claim.expected_value * rho)
+            .sum();
+        Ok(MultiClaimVerifier {
+            verifier: Verifier::init_deferred(
+                claim,
+                PolynomialInfo {
+                    num_vars,
+                    max_degree: 2,
+                },
+            ),
+            points: claims.iter().map(|claim| claim.point.clone()).collect(),
+            coefficients,
+        })
+    }
+
+    /// The random `rho_j`, sent to the prover before the first round.
+    pub fn coefficients(&self) -> &[F] {
+        &self.coefficients
+    }
+
+    pub fn verify_round(
+        &mut self,
+        current_poly: DensePolynomial<F>,
+        rng: &mut dyn RngCore,
+    ) -> Result<Option<F>, SumcheckError> {
+        self.verifier.verify_round(current_poly, rng)
+    }
+
+    /// Turn sumcheck's claim `E(r) * f(r) = v` into `f(r) = v / E(r)`, where the verifier evaluates
+    /// `E(r) = sum_j rho_j * eq(z_j, r)` itself.
+    pub fn subclaim(&self) -> Result<SubClaim<F>, SumcheckError> {
+        let subclaim = self
+            .verifier
+            .subclaim()
+            .ok_or(SumcheckError::ProtocolNotFinished)?;
+        let e: F = self
+            .points
+            .iter()
+            .zip(&self.coefficients)
+            .map(|(z, rho)| *rho * eq(z, &subclaim.point))
+            .sum();
+        // E(r) = 0 only happens with probability n / |F|, and then nothing can be learned about f
+        let e_inverse = e.inverse().ok_or(SumcheckError::FinalCheckFailed)?;
+        Ok(SubClaim {
+            point: subclaim.point.clone(),
+            expected_value: subclaim.expected_value * e_inverse,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{One, UniformRand, Zero};
+    use ark_poly::{
+        multivariate::{SparsePolynomial as SparseMVPolynomial, SparseTerm, Term},
+        DenseMVPolynomial, DenseUVPolynomial,
+    };
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::fields::goldilocks::Goldilocks;
+    use crate::lagrange::univariate::univariate::interpolate_at_points;
+    use crate::sumcheck::cheating::SoundnessReport;
+    use crate::sumcheck::test_utils::Fq;
+    use crate::sumcheck::{line, restrict_mle_to_curve, restrict_mle_to_line, restrict_to_line};
+
+    fn random_point(n: usize, rng: &mut dyn RngCore) -> Vec<Goldilocks> {
+        (0..n).map(|_| Goldilocks::rand(rng)).collect()
+    }
+
+    fn claim_at(f: &DenseMLE<Goldilocks>, point: Vec<Goldilocks>) -> SubClaim<Goldilocks> {
+        SubClaim {
+            expected_value: f.evaluate(&point),
+            point,
+        }
+    }
+
+    #[test]
+    fn restriction_agrees_with_line() {
+        // 2 x_1^3 + x_1 x_3 + x_2 x_3
+        let g = SparseMVPolynomial::from_coefficients_slice(
+            3,
+            &[
+                (Goldilocks::from(2u64), SparseTerm::new(vec![(0, 3)])),
+                (
+                    Goldilocks::from(1u64),
+                    SparseTerm::new(vec![(0, 1), (2, 1)]),
+                ),
+                (
+                    Goldilocks::from(1u64),
+                    SparseTerm::new(vec![(1, 1), (2, 1)]),
+                ),
+            ],
+        );
+        let rng = &mut test_rng();
+        let (b, c) = (random_point(3, rng), random_point(3, rng));
+        let q = restrict_to_line(&g, &b, &c);
+        assert_eq!(q.degree(), 3);
+        for _ in 0..5 {
+            let t = Goldilocks::rand(rng);
+            assert_eq!(q.evaluate(&t), g.evaluate(&line(&b, &c, t)));
+        }
+
+        let f = DenseMLE::new(3, (1..9u64).map(Goldilocks::from).collect());
+        let q = restrict_mle_to_line(&f, &b, &c);
+        let t = Goldilocks::rand(rng);
+        assert_eq!(q.evaluate(&t), f.evaluate(&line(&b, &c, t)));
+    }
+
+    #[test]
+    fn two_to_one_keeps_true_claims_true() {
+        let rng = &mut test_rng();
+        let f = DenseMLE::new(4, random_point(16, rng));
+        let first = claim_at(&f, random_point(4, rng));
+        let second = claim_at(&f, random_point(4, rng));
+        let q = restrict_mle_to_line(&f, &first.point, &second.point);
+        let claim = reduce_two_to_one(&first, &second, &q, 4, rng).unwrap();
+        assert!(claim.check(&f).unwrap());
+    }
+
+    #[test]
+    fn two_to_one_catches_false_claim() {
+        let rng = &mut test_rng();
+        let f = DenseMLE::new(4, random_point(16, rng));
+        let first = claim_at(&f, random_point(4, rng));
+        let mut second = claim_at(&f, random_point(4, rng));
+        second.expected_value += Goldilocks::one();
+
+        // the honest restriction exposes the false claim right away
+        let q = restrict_mle_to_line(&f, &first.point, &second.point);
+        assert_eq!(
+            reduce_two_to_one(&first, &second, &q, 4, rng),
+            Err(ReductionError::ClaimMismatch { index: 1 })
+        );
+
+        // a q matching both claims isn't W on the line, so the new claim is false
+        let shifted = &q
+            + &DensePolynomial::from_coefficients_vec(vec![
+                Goldilocks::from(0u64),
+                Goldilocks::one(),
+            ]);
+        let claim = reduce_two_to_one(&first, &second, &shifted, 4, rng).unwrap();
+        assert!(!claim.check(&f).unwrap());
+
+        let too_high = DensePolynomial::from_coefficients_vec(vec![Goldilocks::one(); 6]);
+        assert_eq!(
+            reduce_two_to_one(&first, &second, &too_high, 4, rng),
+            Err(ReductionError::DegreeTooHigh {
+                degree: 5,
+                max_degree: 4
+            })
+        );
+    }
+
+    const TRIALS: usize = 1000;
+
+    // three claims about a random MLE in 3 variables, of which the second is off by one
+    fn false_claims(rng: &mut dyn RngCore) -> (DenseMLE<Fq>, Vec<SubClaim<Fq>>) {
+        let f = DenseMLE::new(3, (0..8).map(|_| Fq::rand(rng)).collect());
+        let mut claims: Vec<SubClaim<Fq>> = (0..3)
+            .map(|_| {
+                let point: Vec<Fq> = (0..3).map(|_| Fq::rand(rng)).collect();
+                SubClaim {
+                    expected_value: f.evaluate(&point),
+                    point,
+                }
+            })
+            .collect();
+        claims[1].expected_value += Fq::one();
+        (f, claims)
+    }
+
+    fn run_multi_claim(
+        f: &DenseMLE<Fq>,
+        claims: &[SubClaim<Fq>],
+        rng: &mut dyn RngCore,
+    ) -> Result<SubClaim<Fq>, SumcheckError> {
+        let mut verifier = MultiClaimVerifier::init(claims, rng)?;
+        let points: Vec<Vec<Fq>> = claims.iter().map(|c| c.point.clone()).collect();
+        let mut prover = MultiClaimProver::init(f, &points, verifier.coefficients())?;
+        let mut r = None;
+        for _ in 0..prover.num_vars() {
+            r = verifier.verify_round(prover.prove_round(r), rng)?;
+        }
+        verifier.subclaim()
+    }
+
+    #[test]
+    fn curve_passes_through_points() {
+        let rng = &mut test_rng();
+        let points: Vec<Vec<Goldilocks>> = (0..4).map(|_| random_point(3, rng)).collect();
+        for (j, point) in points.iter().enumerate() {
+            assert_eq!(&curve(&points, Goldilocks::from(j as u64)), point);
+        }
+        let t = Goldilocks::rand(rng);
+        assert_eq!(curve(&points[..2], t), line(&points[0], &points[1], t));
+    }
+
+    #[test]
+    fn many_to_one_keeps_true_claims_true() {
+        let rng = &mut test_rng();
+        let f = DenseMLE::new(4, random_point(16, rng));
+        let claims: Vec<SubClaim<Goldilocks>> =
+            (0..4).map(|_| claim_at(&f, random_point(4, rng))).collect();
+        let points: Vec<Vec<Goldilocks>> = claims.iter().map(|c| c.point.clone()).collect();
+
+        let q = restrict_mle_to_curve(&f, &points);
+        assert_eq!(q.degree(), 12);
+        let claim = reduce_on_curve(&claims, &q, 4, rng).unwrap();
+        assert!(claim.check(&f).unwrap());
+
+        let mut verifier = MultiClaimVerifier::init(&claims, rng).unwrap();
+        let mut prover = MultiClaimProver::init(&f, &points, verifier.coefficients()).unwrap();
+        let mut r = None;
+        for _ in 0..prover.num_vars() {
+            r = verifier.verify_round(prover.prove_round(r), rng).unwrap();
+        }
+        assert!(verifier.subclaim().unwrap().check(&f).unwrap());
+    }
+
+    #[test]
+    fn multi_claim_rejects_points_of_different_lengths() {
+        let rng = &mut test_rng();
+        let (f, mut claims) = false_claims(rng);
+        claims[2].point.pop();
+        assert_eq!(
+            run_multi_claim(&f, &claims, rng),
+            Err(SumcheckError::NumVarsMismatch {
+                declared: 3,
+                actual: 2
+            })
+        );
+    }
+
+    #[test]
+    fn random_combination_soundness() {
+        let rng = &mut test_rng();
+        let mut accepted = 0;
+        for _ in 0..TRIALS {
+            let (f, claims) = false_claims(rng);
+            // the honest prover only gets through if rho_1 = 0 hides the false claim
+            if let Ok(claim) = run_multi_claim(&f, &claims, rng) {
+                accepted += claim.check(&f).unwrap() as usize;
+            }
+        }
+        let report = SoundnessReport {
+            trials: TRIALS,
+            accepted,
+            // 1 / |F| for the combination, 2 n / |F| for the sumcheck
+            bound: 7.0 / 71.0,
+        };
+        assert!(report.accepted > 0);
+        assert!(report.acceptance_rate() <= report.bound);
+    }
+
+    #[test]
+    fn curve_soundness() {
+        let rng = &mut test_rng();
+        let nodes: Vec<Fq> = (0..3u64).map(Fq::from).collect();
+        let mut accepted = 0;
+        for _ in 0..TRIALS {
+            let (f, claims) = false_claims(rng);
+            let points: Vec<Vec<Fq>> = claims.iter().map(|c| c.point.clone()).collect();
+            let q = restrict_mle_to_curve(&f, &points);
+            assert_eq!(
+                reduce_on_curve(&claims, &q, 3, rng),
+                Err(ReductionError::ClaimMismatch { index: 1 })
+            );
+
+            // add one times the Lagrange polynomial of node 1, so q matches every claim
+            let lagrange = interpolate_at_points(&nodes, &[Fq::zero(), Fq::one(), Fq::zero()]);
+            let cheat = &q + &lagrange;
+            let claim = reduce_on_curve(&claims, &cheat, 3, rng).unwrap();
+            accepted += claim.check(&f).unwrap() as usize;
+        }
+        let report = SoundnessReport {
+            trials: TRIALS,
+            accepted,
+            // q and the cheat are different polynomials of degree (k - 1) * n = 6
+            bound: 6.0 / 71.0,
+        };
+        assert!(report.acceptance_rate() <= report.bound);
+    }
+}
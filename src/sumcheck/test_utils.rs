@@ -0,0 +1,23 @@
+use ark_ff::{Fp64, MontBackend, MontConfig};
+use ark_poly::{
+    multivariate::{SparsePolynomial as SparseMVPolynomial, SparseTerm, Term},
+    DenseMVPolynomial,
+};
+
+#[derive(MontConfig)]
+#[modulus = "71"]
+#[generator = "3"]
+pub struct FqConfig;
+pub type Fq = Fp64<MontBackend<FqConfig, 1>>;
+
+/// `g = 2 x_1^3 + x_1 x_3 + x_2 x_3`, which sums to 12 over the hypercube.
+pub fn sample_poly() -> SparseMVPolynomial<Fq, SparseTerm> {
+    SparseMVPolynomial::from_coefficients_slice(
+        3,
+        &[
+            (Fq::from(2), SparseTerm::new(vec![(0, 3)])),
+            (Fq::from(1), SparseTerm::new(vec![(0, 1), (2, 1)])),
+            (Fq::from(1), SparseTerm::new(vec![(1, 1), (2, 1)])),
+        ],
+    )
+}
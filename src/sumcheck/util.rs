@@ -2,17 +2,36 @@ pub mod util {
     use ark_ff::Field;
     use ark_poly::{
         multivariate::{SparsePolynomial as SparseMVPolynomial, SparseTerm, Term},
-        DenseMVPolynomial, Polynomial,
+        univariate::DensePolynomial,
+        DenseMVPolynomial, DenseUVPolynomial, Polynomial,
     };
     use itertools::Itertools;
+    #[cfg(feature = "rayon")]
+    use rayon::prelude::*;
 
-    /// Given a multivariate polynomial `g` over {x_1, ..., x_N}, evaluate it at all challenge points `0..x`,
+    use crate::lagrange::{
+        multivariate::multivariate::DenseMLE,
+        univariate::univariate::{interpolate, node},
+    };
+    use crate::sumcheck::domain::SumDomain;
+
+    /// Given a multivariate polynomial `g` over {x_1, ..., x_n}, evaluate it at all challenge points `0..x`,
     /// variable at `x` will remain the X of the univariate polynomial,
-    /// and the rest of the variables will be evaluated at a boolean hypercube of size {0,1}^(g.degree() - index).
-    pub fn reduce_poly_to_univar_at_x<F: Field, const N: usize>(
+    /// and the rest of the variables will be evaluated at a boolean hypercube of size {0,1}^(g.num_vars() - index - 1).
+    pub fn reduce_poly_to_univar_at_x<F: Field>(
         g: SparseMVPolynomial<F, SparseTerm>,
         x_i: usize,
         challenges: Vec<F>,
+    ) -> SparseMVPolynomial<F, SparseTerm> {
+        reduce_poly_to_univar_over_domain(g, x_i, challenges, &SumDomain::boolean())
+    }
+
+    /// Same as `reduce_poly_to_univar_at_x`, but the variables after `x` are summed over `H^(g.num_vars() - index - 1)`.
+    pub fn reduce_poly_to_univar_over_domain<F: Field>(
+        g: SparseMVPolynomial<F, SparseTerm>,
+        x_i: usize,
+        challenges: Vec<F>,
+        domain: &SumDomain<F>,
     ) -> SparseMVPolynomial<F, SparseTerm> {
         // empty polynomial
         let mut accumulator = SparseMVPolynomial::<F, SparseTerm>::from_coefficients_slice(
@@ -20,12 +39,11 @@ pub mod util {
             &[(F::zero(), SparseTerm::new(vec![]))],
         );
 
-        // can we make this more efficient with a Vec?
-        let mut partial_point: [Option<F>; N] = [None; N];
+        let mut partial_point: Vec<Option<F>> = vec![None; g.num_vars()];
 
-        // generate hypercube {0,1}^(g.degree() - x_i)
+        // generate the grid H^(g.num_vars() - x_i - 1)
         let hypercube = ((x_i + 1)..g.num_vars())
-            .map(|_| 0..2u64)
+            .map(|_| domain.points().iter())
             .multi_cartesian_product();
 
         // fill out the partial point with challenges
@@ -35,36 +53,220 @@ pub mod util {
 
         if x_i + 1 == g.num_vars() {
             // final round
-            println!("partial point is {:?}", partial_point);
-            accumulator += &g.partial_evaluate(&partial_point.try_into().unwrap())
+            accumulator += &partial_evaluate(&g, &partial_point)
         } else {
             // middle rounds
             for b in hypercube {
-                for (index, bool_elem) in b.iter().enumerate() {
-                    // fill out the partial point with the boolean hypercube
-                    partial_point[index + x_i + 1] = Some(F::from(*bool_elem));
+                for (index, elem) in b.iter().enumerate() {
+                    // fill out the partial point with the grid
+                    partial_point[index + x_i + 1] = Some(**elem);
                 }
 
-                accumulator += &g.partial_evaluate(&partial_point.try_into().unwrap());
+                accumulator += &partial_evaluate(&g, &partial_point);
             }
         }
 
         accumulator
     }
 
-    pub fn get_claim<F: Field, const N: usize>(g: SparseMVPolynomial<F, SparseTerm>) -> F {
+    /// `g` with the variables that are `Some` in `point` fixed to their values. The other variables are left
+    /// free under their original indices, and `num_vars` of the result counts only them.
+    fn partial_evaluate<F: Field>(
+        g: &SparseMVPolynomial<F, SparseTerm>,
+        point: &[Option<F>],
+    ) -> SparseMVPolynomial<F, SparseTerm> {
+        let terms = g
+            .terms()
+            .iter()
+            .map(|(coeff, term)| {
+                let mut coeff = *coeff;
+                let mut free = vec![];
+                for &(var, power) in term.iter() {
+                    match point[var] {
+                        Some(value) => coeff *= value.pow([power as u64]),
+                        None => free.push((var, power)),
+                    }
+                }
+                (coeff, SparseTerm::new(free))
+            })
+            .collect();
+        let mut result = SparseMVPolynomial::from_coefficients_vec(g.num_vars(), terms);
+        result.num_vars = point.iter().filter(|x| x.is_none()).count();
+        result
+    }
+
+    /// Convert the output of `reduce_poly_to_univar_at_x`, a multivariate polynomial in which only a single
+    /// variable is left, into a proper univariate polynomial in that variable.
+    pub fn to_univariate<F: Field>(poly: &SparseMVPolynomial<F, SparseTerm>) -> DensePolynomial<F> {
+        let mut coeffs = vec![F::zero(); poly.degree() + 1];
+        for (coeff, term) in poly.terms() {
+            coeffs[term.degree()] += coeff;
+        }
+        DensePolynomial::from_coefficients_vec(coeffs)
+    }
+
+    /// The line `l(t) = b + t * (c - b)` through `l(0) = b` and `l(1) = c`, evaluated at `t`.
+    pub fn line<F: Field>(b: &[F], c: &[F], t: F) -> Vec<F> {
+        assert_eq!(b.len(), c.len(), "points should have the same dimension");
+        b.iter()
+            .zip(c)
+            .map(|(b_j, c_j)| *b_j + t * (*c_j - b_j))
+            .collect()
+    }
+
+    /// `q(t) = g(l(t))` for the line through `b` and `c`. Every variable becomes the linear
+    /// polynomial `b_j + t * (c_j - b_j)`, so `q` has at most the total degree of `g`.
+    pub fn restrict_to_line<F: Field>(
+        g: &SparseMVPolynomial<F, SparseTerm>,
+        b: &[F],
+        c: &[F],
+    ) -> DensePolynomial<F> {
+        assert_eq!(
+            b.len(),
+            g.num_vars(),
+            "points should have one coordinate per variable"
+        );
+        assert_eq!(b.len(), c.len(), "points should have the same dimension");
+        let lines: Vec<DensePolynomial<F>> = b
+            .iter()
+            .zip(c)
+            .map(|(b_j, c_j)| DensePolynomial::from_coefficients_vec(vec![*b_j, *c_j - b_j]))
+            .collect();
+        let mut q = DensePolynomial::from_coefficients_vec(vec![]);
+        for (coeff, term) in g.terms() {
+            let mut product = DensePolynomial::from_coefficients_vec(vec![*coeff]);
+            for &(var, power) in term.iter() {
+                for _ in 0..power {
+                    product = product.naive_mul(&lines[var]);
+                }
+            }
+            q += &product;
+        }
+        q
+    }
+
+    /// `restrict_to_line` for an MLE given by its table. `q` has degree at most `num_vars`, so it
+    /// is interpolated from `f(l(t))` at the nodes for `t = 0..=num_vars`, which takes O(num_vars * 2^num_vars).
+    pub fn restrict_mle_to_line<F: Field>(f: &DenseMLE<F>, b: &[F], c: &[F]) -> DensePolynomial<F> {
+        assert_eq!(
+            b.len(),
+            f.num_vars(),
+            "points should have one coordinate per variable"
+        );
+        let evals: Vec<F> = (0..=f.num_vars())
+            .map(|t| f.evaluate(&line(b, c, node(t))))
+            .collect();
+        interpolate(&evals)
+    }
+
+    /// The curve `gamma` of degree `k - 1` with `gamma(node(j)) = points[j]` for `j = 0..k`, evaluated at `t`.
+    /// For two points this is `line`.
+    pub fn curve<F: Field>(points: &[Vec<F>], t: F) -> Vec<F> {
+        assert!(!points.is_empty(), "the curve should pass through a point");
+        let k = points.len();
+        // the Lagrange basis polynomials for node(0), ..., node(k - 1), evaluated at t
+        let weights: Vec<F> = (0..k)
+            .map(|j| {
+                let (numerator, denominator) =
+                    (0..k)
+                        .filter(|&m| m != j)
+                        .fold((F::one(), F::one()), |(num, den), m| {
+                            let (j, m): (F, F) = (node(j), node(m));
+                            (num * (t - m), den * (j - m))
+                        });
+                numerator * denominator.inverse().expect("nodes should be distinct")
+            })
+            .collect();
+        (0..points[0].len())
+            .map(|i| points.iter().zip(&weights).map(|(z, w)| z[i] * w).sum())
+            .collect()
+    }
+
+    /// `q(t) = f(gamma(t))` for the curve through `points`, which has degree at most
+    /// `(k - 1) * num_vars` and is interpolated from that many points plus one.
+    pub fn restrict_mle_to_curve<F: Field>(
+        f: &DenseMLE<F>,
+        points: &[Vec<F>],
+    ) -> DensePolynomial<F> {
+        assert!(!points.is_empty(), "the curve should pass through a point");
+        let degree = (points.len() - 1) * f.num_vars();
+        let evals: Vec<F> = (0..=degree)
+            .map(|t| f.evaluate(&curve(points, node(t))))
+            .collect();
+        interpolate(&evals)
+    }
+
+    pub fn get_claim<F: Field>(g: SparseMVPolynomial<F, SparseTerm>) -> F {
+        get_claim_over_domain(g, &SumDomain::boolean())
+    }
+
+    /// The sum of `g` over every point of `H^n`.
+    pub fn get_claim_over_domain<F: Field>(
+        g: SparseMVPolynomial<F, SparseTerm>,
+        domain: &SumDomain<F>,
+    ) -> F {
         let mut accumulator = F::zero();
-        // iterate over the boolean hypercube {0,1}^N
-        for b in (0..N).map(|_| 0..2u64).multi_cartesian_product() {
-            let temp: [F; N] = b
-                .into_iter()
-                .map(|x| F::from(x))
-                .collect::<Vec<_>>()
-                .try_into()
-                .unwrap();
-            accumulator += g.evaluate(&temp.to_vec());
+        // iterate over the grid H^n
+        for b in (0..g.num_vars())
+            .map(|_| domain.points().iter().copied())
+            .multi_cartesian_product()
+        {
+            accumulator += g.evaluate(&b);
         }
 
         accumulator
     }
+
+    /// `get_claim` without enumerating the hypercube. Since `b^e = b` for a bit `b`, a monomial
+    /// `c * prod_j x_j^(e_j)` in `k` of the `n` variables sums to `c * 2^(n - k)` over {0,1}^n.
+    pub fn get_claim_fast<F: Field>(g: &SparseMVPolynomial<F, SparseTerm>) -> F {
+        get_claim_over_domain_fast(g, &SumDomain::boolean())
+    }
+
+    /// `get_claim_over_domain` term by term. The sum of a monomial over `H^n` factors per variable:
+    /// `c * prod_j (sum_{h in H} h^(e_j)) * |H|^(n - k)`, where `k` is the number of variables in the monomial.
+    pub fn get_claim_over_domain_fast<F: Field>(
+        g: &SparseMVPolynomial<F, SparseTerm>,
+        domain: &SumDomain<F>,
+    ) -> F {
+        let size = F::from(domain.size() as u64);
+        let power_sum = |e: usize| -> F { domain.points().iter().map(|h| h.pow([e as u64])).sum() };
+        let term_sum = |(c, term): &(F, SparseTerm)| -> F {
+            let free_vars = g.num_vars().saturating_sub(term.len()) as u64;
+            term.iter()
+                .fold(*c * size.pow([free_vars]), |acc, &(_, e)| {
+                    acc * power_sum(e)
+                })
+        };
+
+        #[cfg(feature = "rayon")]
+        return g.terms().par_iter().map(term_sum).sum();
+        #[cfg(not(feature = "rayon"))]
+        return g.terms().iter().map(term_sum).sum();
+    }
+
+    /// `get_claim` with the hypercube split into chunks that are evaluated in parallel.
+    /// Each chunk reuses a single buffer for its points instead of allocating one per point.
+    #[cfg(feature = "rayon")]
+    pub fn get_claim_parallel<F: Field>(g: &SparseMVPolynomial<F, SparseTerm>) -> F {
+        let n = g.num_vars();
+        // 2^chunk_bits points per chunk
+        let chunk_bits = n.min(10);
+        (0..1u64 << (n - chunk_bits))
+            .into_par_iter()
+            .map(|chunk| {
+                let mut point = vec![F::zero(); n];
+                let mut sum = F::zero();
+                for low in 0..1u64 << chunk_bits {
+                    let b = (chunk << chunk_bits) | low;
+                    // x_1 is the most significant bit, like in `multi_cartesian_product`
+                    for (j, x) in point.iter_mut().enumerate() {
+                        *x = F::from((b >> (n - 1 - j)) & 1);
+                    }
+                    sum += g.evaluate(&point);
+                }
+                sum
+            })
+            .sum()
+    }
 }
@@ -0,0 +1,219 @@
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+
+use super::{
+    oracle::{Oracle, OracleError},
+    SumcheckPolynomial,
+};
+use crate::lagrange::{
+    multivariate::multivariate::DenseMLE,
+    univariate::univariate::{interpolate, node},
+};
+
+/// `g(x) = sum_k c_k * prod_j f_{k,j}(x)`, a sum of products of multilinear polynomials.
+///
+/// The MLEs are stored once and the products refer to them by index, so an MLE that appears in
+/// several products (e.g. the `eq` polynomial in Spartan or HyperPlonk) is only folded once per round.
+/// The prover works on the tables directly, exactly like `MleProduct`.
+#[derive(Debug, Clone)]
+pub struct VirtualPolynomial<F: Field> {
+    num_vars: usize,
+    // (c_k, indices of f_{k,1}, ..., f_{k,d_k} into `mles`)
+    products: Vec<(F, Vec<usize>)>,
+    mles: Vec<DenseMLE<F>>,
+}
+
+impl<F: Field> VirtualPolynomial<F> {
+    pub fn new(num_vars: usize) -> Self {
+        VirtualPolynomial {
+            num_vars,
+            products: vec![],
+            mles: vec![],
+        }
+    }
+
+    /// Register an MLE and return the index that products use to refer to it.
+    pub fn add_mle(&mut self, mle: DenseMLE<F>) -> usize {
+        assert_eq!(
+            mle.num_vars(),
+            self.num_vars,
+            "MLE should have the same number of variables as the polynomial"
+        );
+        self.mles.push(mle);
+        self.mles.len() - 1
+    }
+
+    /// Add the term `coefficient * prod_{j in mle_indices} f_j` to the sum.
+    pub fn add_product(&mut self, coefficient: F, mle_indices: Vec<usize>) {
+        assert!(
+            mle_indices.iter().all(|&i| i < self.mles.len()),
+            "product refers to an MLE that has not been added"
+        );
+        self.products.push((coefficient, mle_indices));
+    }
+
+    pub fn products(&self) -> &[(F, Vec<usize>)] {
+        &self.products
+    }
+
+    pub fn mles(&self) -> &[DenseMLE<F>] {
+        &self.mles
+    }
+
+    /// The degree of `g` in each variable, which is the size of the largest product.
+    pub fn max_degree(&self) -> usize {
+        self.products
+            .iter()
+            .map(|(_, indices)| indices.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn evaluate(&self, point: &[F]) -> F {
+        let mle_evals: Vec<F> = self.mles.iter().map(|f| f.evaluate(point)).collect();
+        self.products
+            .iter()
+            .map(|(c, indices)| *c * indices.iter().map(|&i| mle_evals[i]).product::<F>())
+            .sum()
+    }
+
+    /// The sum of `g` over the boolean hypercube, computed directly from the tables.
+    pub fn sum_over_hypercube(&self) -> F {
+        (0..1 << self.num_vars)
+            .map(|b| {
+                self.products
+                    .iter()
+                    .map(|(c, indices)| {
+                        *c * indices
+                            .iter()
+                            .map(|&i| self.mles[i].evaluations()[b])
+                            .product::<F>()
+                    })
+                    .sum::<F>()
+            })
+            .sum()
+    }
+}
+
+impl<F: Field> SumcheckPolynomial<F> for VirtualPolynomial<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn max_degree(&self) -> usize {
+        VirtualPolynomial::max_degree(self)
+    }
+
+    fn round_polynomial(&self, round: usize, _challenges: &[F]) -> DensePolynomial<F> {
+        let degree = self.max_degree();
+        // from `num_vars` rather than the tables, since there may be none
+        let half = 1 << (self.num_vars - 1 - round);
+        let mut evals = vec![F::zero(); degree + 1];
+        // f_j(node(t), b) for every MLE and t = 0..=degree, shared between the products that use f_j
+        let mut mle_values = vec![vec![F::zero(); degree + 1]; self.mles.len()];
+        let nodes: Vec<F> = (0..=degree).map(node).collect();
+        for b in 0..half {
+            for (values, f) in mle_values.iter_mut().zip(&self.mles) {
+                let lo = f.evaluations()[b];
+                let step = f.evaluations()[b + half] - lo;
+                for (v, t) in values.iter_mut().zip(&nodes) {
+                    *v = lo + *t * step;
+                }
+            }
+            for (c, indices) in &self.products {
+                for (t, eval) in evals.iter_mut().enumerate() {
+                    *eval += *c * indices.iter().map(|&i| mle_values[i][t]).product::<F>();
+                }
+            }
+        }
+        interpolate(&evals)
+    }
+
+    fn fix_variable(&mut self, r: F) {
+        self.mles = self.mles.iter().map(|f| f.fix_first_variable(r)).collect();
+    }
+}
+
+impl<F: Field> Oracle<F> for VirtualPolynomial<F> {
+    fn evaluate(&self, point: &[F]) -> Result<F, OracleError> {
+        if point.len() != self.num_vars {
+            return Err(OracleError::InvalidPoint {
+                expected: self.num_vars,
+                got: point.len(),
+            });
+        }
+        Ok(VirtualPolynomial::evaluate(self, point))
+    }
+
+    fn num_vars(&self) -> Option<usize> {
+        Some(self.num_vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{UniformRand, Zero};
+    use ark_std::{rand::Rng, test_rng};
+
+    use super::*;
+    use crate::sumcheck::test_utils::Fq;
+    use crate::sumcheck::{Prover, SumcheckError, Verifier};
+
+    fn random_mle<R: Rng>(num_vars: usize, rng: &mut R) -> DenseMLE<Fq> {
+        DenseMLE::new(
+            num_vars,
+            (0..1 << num_vars).map(|_| Fq::rand(rng)).collect(),
+        )
+    }
+
+    // g = 3 * f_0 * f_1 * f_2 + 5 * f_0 * f_3 + 7 * f_3, with f_0 and f_3 shared between products
+    fn sample_virtual_poly<R: Rng>(num_vars: usize, rng: &mut R) -> VirtualPolynomial<Fq> {
+        let mut g = VirtualPolynomial::new(num_vars);
+        let f: Vec<usize> = (0..4)
+            .map(|_| g.add_mle(random_mle(num_vars, rng)))
+            .collect();
+        g.add_product(Fq::from(3), vec![f[0], f[1], f[2]]);
+        g.add_product(Fq::from(5), vec![f[0], f[3]]);
+        g.add_product(Fq::from(7), vec![f[3]]);
+        g
+    }
+
+    fn run_protocol(g: VirtualPolynomial<Fq>, claim: Fq) -> Result<(), SumcheckError> {
+        let rng = &mut test_rng();
+        let mut verifier = Verifier::init(g.clone(), claim, g.info())?;
+        let mut prover: Prover<Fq, VirtualPolynomial<Fq>> = Prover::init(g)?;
+        let mut r = None;
+        for _ in 0..prover.num_vars() {
+            r = verifier.verify_round(prover.prove_round(r), rng)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn max_degree_is_largest_product() {
+        let g = sample_virtual_poly(3, &mut test_rng());
+        assert_eq!(g.max_degree(), 3);
+    }
+
+    #[test]
+    fn protocol_with_virtual_polynomial() {
+        let g = sample_virtual_poly(4, &mut test_rng());
+        let claim = g.sum_over_hypercube();
+        run_protocol(g.clone(), claim).unwrap();
+        assert_eq!(
+            run_protocol(g, claim + Fq::from(1)),
+            Err(SumcheckError::ClaimMismatch { round: 0 })
+        );
+    }
+
+    #[test]
+    fn protocol_with_empty_virtual_polynomial() {
+        let g = VirtualPolynomial::new(3);
+        assert_eq!(g.sum_over_hypercube(), Fq::zero());
+        run_protocol(g.clone(), Fq::zero()).unwrap();
+        assert_eq!(
+            run_protocol(g, Fq::from(1)),
+            Err(SumcheckError::ClaimMismatch { round: 0 })
+        );
+    }
+}
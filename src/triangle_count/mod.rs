@@ -0,0 +1,473 @@
+use std::fmt;
+
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+use ark_std::rand::RngCore;
+
+use crate::lagrange::{
+    multivariate::multivariate::DenseMLE,
+    univariate::univariate::{interpolate, node},
+};
+use crate::sumcheck::{
+    oracle::{Oracle, OracleError},
+    run_sumcheck, SumcheckError, SumcheckPolynomial, Transcript,
+};
+
+/// A simple undirected graph on the vertices `0..num_vertices`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph {
+    num_vertices: usize,
+    edges: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdgeListError {
+    /// A line that is not a comment and not two vertex indices.
+    InvalidLine(String),
+    /// `A(v, v) = 1` would be counted as part of a triangle, so self-loops are rejected.
+    SelfLoop(usize),
+    VertexOutOfRange {
+        vertex: usize,
+        num_vertices: usize,
+    },
+    /// A row of an adjacency matrix with a different length than the number of rows.
+    NotSquare {
+        row: usize,
+        len: usize,
+        num_vertices: usize,
+    },
+    /// `A(u, v) != A(v, u)`, which an undirected graph can't have.
+    Asymmetric(usize, usize),
+}
+
+impl fmt::Display for EdgeListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EdgeListError::InvalidLine(line) => write!(f, "invalid edge: {}", line),
+            EdgeListError::SelfLoop(v) => write!(f, "self-loop at vertex {}", v),
+            EdgeListError::VertexOutOfRange {
+                vertex,
+                num_vertices,
+            } => write!(
+                f,
+                "vertex {} is out of range for {} vertices",
+                vertex, num_vertices
+            ),
+            EdgeListError::NotSquare {
+                row,
+                len,
+                num_vertices,
+            } => write!(
+                f,
+                "row {} has {} entries, expected {}",
+                row, len, num_vertices
+            ),
+            EdgeListError::Asymmetric(u, v) => {
+                write!(
+                    f,
+                    "adjacency matrix differs at ({}, {}) and ({}, {})",
+                    u, v, v, u
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for EdgeListError {}
+
+impl Graph {
+    /// Duplicate edges and both orientations of the same edge are allowed.
+    pub fn from_edges(
+        num_vertices: usize,
+        edges: &[(usize, usize)],
+    ) -> Result<Self, EdgeListError> {
+        let mut normalized = vec![];
+        for &(u, v) in edges {
+            if u == v {
+                return Err(EdgeListError::SelfLoop(u));
+            }
+            if let Some(vertex) = [u, v].into_iter().find(|&w| w >= num_vertices) {
+                return Err(EdgeListError::VertexOutOfRange {
+                    vertex,
+                    num_vertices,
+                });
+            }
+            normalized.push((u.min(v), u.max(v)));
+        }
+        normalized.sort_unstable();
+        normalized.dedup();
+        Ok(Graph {
+            num_vertices,
+            edges: normalized,
+        })
+    }
+
+    /// Parse one `u v` pair per line, as in the SNAP datasets. Lines starting with `#` or `%` are
+    /// comments, and the graph has as many vertices as the largest index plus one.
+    pub fn parse_edge_list(input: &str) -> Result<Self, EdgeListError> {
+        let mut edges = vec![];
+        for line in input.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with('%') {
+                continue;
+            }
+            let vertices: Vec<usize> = line
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|_| EdgeListError::InvalidLine(line.into()))?;
+            match vertices[..] {
+                [u, v] => edges.push((u, v)),
+                _ => return Err(EdgeListError::InvalidLine(line.into())),
+            }
+        }
+        let num_vertices = edges.iter().map(|&(u, v)| u.max(v) + 1).max().unwrap_or(0);
+        Graph::from_edges(num_vertices, &edges)
+    }
+
+    /// The graph with an edge `(u, v)` for every `matrix[u][v]` set. The matrix has to be square and
+    /// symmetric, with nothing set on the diagonal.
+    pub fn from_adjacency_matrix(matrix: &[Vec<bool>]) -> Result<Self, EdgeListError> {
+        let num_vertices = matrix.len();
+        if let Some((row, entries)) = matrix
+            .iter()
+            .enumerate()
+            .find(|(_, entries)| entries.len() != num_vertices)
+        {
+            return Err(EdgeListError::NotSquare {
+                row,
+                len: entries.len(),
+                num_vertices,
+            });
+        }
+        let mut edges = vec![];
+        for (u, row) in matrix.iter().enumerate() {
+            for (v, &adjacent) in row.iter().enumerate().skip(u) {
+                if adjacent != matrix[v][u] {
+                    return Err(EdgeListError::Asymmetric(u, v));
+                }
+                if adjacent {
+                    edges.push((u, v));
+                }
+            }
+        }
+        // the diagonal is caught as a self-loop
+        Graph::from_edges(num_vertices, &edges)
+    }
+
+    pub fn num_vertices(&self) -> usize {
+        self.num_vertices
+    }
+
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    /// The number of index bits `k` with the vertex count padded to `2^k`.
+    pub fn num_bits(&self) -> usize {
+        self.num_vertices
+            .max(1)
+            .next_power_of_two()
+            .trailing_zeros() as usize
+    }
+
+    /// The MLE of the adjacency matrix padded to `2^k x 2^k`, with the row index as the first `k`
+    /// variables. The padding vertices are isolated, so they add no triangles.
+    pub fn adjacency_mle<F: Field>(&self) -> DenseMLE<F> {
+        let k = self.num_bits();
+        let mut evaluations = vec![F::zero(); 1 << (2 * k)];
+        for &(u, v) in &self.edges {
+            evaluations[(u << k) | v] = F::one();
+            evaluations[(v << k) | u] = F::one();
+        }
+        DenseMLE::new(2 * k, evaluations)
+    }
+
+    /// The number of triangles, by checking every triple of vertices.
+    pub fn count_triangles(&self) -> u64 {
+        let n = self.num_vertices;
+        let mut adjacent = vec![false; n * n];
+        for &(u, v) in &self.edges {
+            adjacent[u * n + v] = true;
+            adjacent[v * n + u] = true;
+        }
+        let mut count = 0;
+        for &(u, v) in &self.edges {
+            count += (v + 1..n)
+                .filter(|&w| adjacent[u * n + w] && adjacent[v * n + w])
+                .count() as u64;
+        }
+        count
+    }
+}
+
+/// `g(x, y, z) = A(x, y) * A(y, z) * A(x, z)` over `3k` variables, ordered `x`, then `y`, then `z`.
+/// Every ordered triangle `(x, y, z)` contributes 1, so `g` sums to `6 T`.
+///
+/// Each variable occurs in exactly two of the factors, so `g` has degree 2 in every variable, and each
+/// factor only depends on `2k` of the variables. Instead of blowing the three tables up to `2^(3k)`
+/// entries for an `MleProduct`, the prover keeps the three `n^2` tables and folds only the two that
+/// contain the variable of the current round. Round `i` still reads all `2^(3k - i + 1)` points of
+/// the remaining cube, for `O(n^3)` in total.
+#[derive(Debug, Clone)]
+pub struct TrianglePolynomial<F: Field> {
+    num_bits: usize,
+    adjacency: DenseMLE<F>,
+    xy: DenseMLE<F>,
+    yz: DenseMLE<F>,
+    xz: DenseMLE<F>,
+    round: usize,
+}
+
+// the groups of variables each table is over, in the order of its index bits
+const XY: (usize, usize) = (0, 1);
+const YZ: (usize, usize) = (1, 2);
+const XZ: (usize, usize) = (0, 2);
+
+impl<F: Field> TrianglePolynomial<F> {
+    pub fn new(graph: &Graph) -> Self {
+        let adjacency = graph.adjacency_mle();
+        TrianglePolynomial {
+            num_bits: graph.num_bits(),
+            xy: adjacency.clone(),
+            yz: adjacency.clone(),
+            xz: adjacency.clone(),
+            adjacency,
+            round: 0,
+        }
+    }
+
+    /// `g(point)` for `point = (x, y, z)`, from the unfolded adjacency MLE.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        let (x, rest) = point.split_at(self.num_bits);
+        let (y, z) = rest.split_at(self.num_bits);
+        let a = |u: &[F], v: &[F]| self.adjacency.evaluate(&[u, v].concat());
+        a(x, y) * a(y, z) * a(x, z)
+    }
+
+    // how many variables of x, y and z are not fixed yet
+    fn remaining(&self) -> [usize; 3] {
+        let k = self.num_bits;
+        let fixed = |group: usize| self.round.saturating_sub(group * k).min(k);
+        [k - fixed(0), k - fixed(1), k - fixed(2)]
+    }
+
+    fn tables(&mut self, group: usize) -> Vec<&mut DenseMLE<F>> {
+        match group {
+            0 => vec![&mut self.xy, &mut self.xz],
+            1 => vec![&mut self.xy, &mut self.yz],
+            _ => vec![&mut self.yz, &mut self.xz],
+        }
+    }
+}
+
+impl<F: Field> SumcheckPolynomial<F> for TrianglePolynomial<F> {
+    fn num_vars(&self) -> usize {
+        3 * self.num_bits
+    }
+
+    fn max_degree(&self) -> usize {
+        2
+    }
+
+    fn round_polynomial(&self, _round: usize, _challenges: &[F]) -> DensePolynomial<F> {
+        let m = self.remaining();
+        let group = (0..3).find(|&g| m[g] > 0).expect("there is a round left");
+        let top = 1 << (m[group] - 1);
+
+        // f(t, b) = lo + t * (hi - lo) for the tables over the current variable, else constant
+        let two = node::<F>(2);
+        let factor = |table: &DenseMLE<F>, (a, b): (usize, usize), bits: [usize; 3]| -> [F; 3] {
+            let index = |bits: [usize; 3]| (bits[a] << m[b]) | bits[b];
+            let lo = table.evaluations()[index(bits)];
+            if group != a && group != b {
+                return [lo; 3];
+            }
+            let mut flipped = bits;
+            flipped[group] |= top;
+            let hi = table.evaluations()[index(flipped)];
+            [lo, hi, lo + two * (hi - lo)]
+        };
+
+        let mut evals = [F::zero(); 3];
+        for x in 0..1 << m[0] {
+            for y in 0..1 << m[1] {
+                for z in 0..1 << m[2] {
+                    let bits = [x, y, z];
+                    if bits[group] & top != 0 {
+                        continue;
+                    }
+                    let xy = factor(&self.xy, XY, bits);
+                    let yz = factor(&self.yz, YZ, bits);
+                    let xz = factor(&self.xz, XZ, bits);
+                    for t in 0..3 {
+                        evals[t] += xy[t] * yz[t] * xz[t];
+                    }
+                }
+            }
+        }
+        interpolate(&evals)
+    }
+
+    fn fix_variable(&mut self, r: F) {
+        let m = self.remaining();
+        let group = (0..3)
+            .find(|&g| m[g] > 0)
+            .expect("there is a variable left");
+        // the current group is the first remaining one, so it is the leading variable of both tables
+        for table in self.tables(group) {
+            *table = table.fix_first_variable(r);
+        }
+        self.round += 1;
+    }
+}
+
+impl<F: Field> Oracle<F> for TrianglePolynomial<F> {
+    fn evaluate(&self, point: &[F]) -> Result<F, OracleError> {
+        if point.len() != 3 * self.num_bits {
+            return Err(OracleError::InvalidPoint {
+                expected: 3 * self.num_bits,
+                got: point.len(),
+            });
+        }
+        Ok(TrianglePolynomial::evaluate(self, point))
+    }
+
+    fn num_vars(&self) -> Option<usize> {
+        Some(3 * self.num_bits)
+    }
+}
+
+/// Prove that `graph` has `triangles` triangles, i.e. that `g` sums to `6 * triangles`. The verifier
+/// evaluates the adjacency MLE at three points for the final check, which takes `O(n^2)`.
+pub fn prove_triangle_count<F: Field>(
+    graph: &Graph,
+    triangles: u64,
+    rng: &mut dyn RngCore,
+) -> Result<Transcript<F>, SumcheckError> {
+    run_sumcheck(TrianglePolynomial::new(graph), F::from(6 * triangles), rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::fields::goldilocks::Goldilocks;
+
+    // a square 0-1-2-3 with both diagonals is K4, with 4 triangles; 4-5 hangs off to the side
+    const EDGES: &str = "# K4 and one more edge
+0 1
+1 2
+2 3
+3 0
+0 2
+1 3
+4 5
+5 4
+";
+
+    #[test]
+    fn parse_and_count() {
+        let graph = Graph::parse_edge_list(EDGES).unwrap();
+        assert_eq!(graph.num_vertices(), 6);
+        assert_eq!(graph.edges().len(), 7);
+        assert_eq!(graph.num_bits(), 3);
+        assert_eq!(graph.count_triangles(), 4);
+
+        assert_eq!(
+            Graph::parse_edge_list("0 1\n2 2"),
+            Err(EdgeListError::SelfLoop(2))
+        );
+        assert_eq!(
+            Graph::parse_edge_list("0 1 2"),
+            Err(EdgeListError::InvalidLine("0 1 2".into()))
+        );
+    }
+
+    #[test]
+    fn polynomial_sums_to_six_times_triangles() {
+        let graph = Graph::parse_edge_list(EDGES).unwrap();
+        let g = TrianglePolynomial::<Goldilocks>::new(&graph);
+        let k = graph.num_bits();
+        let bits = |v: usize| {
+            (0..k)
+                .rev()
+                .map(move |j| Goldilocks::from(((v >> j) & 1) as u64))
+        };
+        let n = 1 << k;
+        let mut sum = Goldilocks::from(0u64);
+        for x in 0..n {
+            for y in 0..n {
+                for z in 0..n {
+                    let point: Vec<Goldilocks> = bits(x).chain(bits(y)).chain(bits(z)).collect();
+                    sum += g.evaluate(&point);
+                }
+            }
+        }
+        assert_eq!(sum, Goldilocks::from(24u64));
+    }
+
+    #[test]
+    fn prove_triangles_of_padded_graph() {
+        let graph = Graph::parse_edge_list(EDGES).unwrap();
+        let rng = &mut test_rng();
+        let transcript = prove_triangle_count::<Goldilocks>(&graph, 4, rng).unwrap();
+        assert_eq!(transcript.round_polynomials.len(), 9);
+        assert!(transcript
+            .round_polynomials
+            .iter()
+            .all(|p| p.coeffs.len() <= 3));
+
+        assert_eq!(
+            prove_triangle_count::<Goldilocks>(&graph, 5, rng),
+            Err(SumcheckError::ClaimMismatch { round: 0 })
+        );
+    }
+
+    #[test]
+    fn triangle_free_graph() {
+        // a 5-cycle has no triangles
+        let graph = Graph::from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]).unwrap();
+        assert_eq!(graph.count_triangles(), 0);
+        assert_eq!(
+            Graph::from_edges(5, &[(0, 1), (1, 5)]),
+            Err(EdgeListError::VertexOutOfRange {
+                vertex: 5,
+                num_vertices: 5
+            })
+        );
+        prove_triangle_count::<Goldilocks>(&graph, 0, &mut test_rng()).unwrap();
+    }
+
+    #[test]
+    fn graph_from_adjacency_matrix() {
+        // K4 as a matrix
+        let k4: Vec<Vec<bool>> = (0..4).map(|u| (0..4).map(|v| u != v).collect()).collect();
+        let graph = Graph::from_adjacency_matrix(&k4).unwrap();
+        assert_eq!(graph.edges().len(), 6);
+        assert_eq!(graph.count_triangles(), 4);
+
+        let mut asymmetric = k4.clone();
+        asymmetric[3][1] = false;
+        assert_eq!(
+            Graph::from_adjacency_matrix(&asymmetric),
+            Err(EdgeListError::Asymmetric(1, 3))
+        );
+        let mut not_square = k4.clone();
+        not_square[2].pop();
+        assert_eq!(
+            Graph::from_adjacency_matrix(&not_square),
+            Err(EdgeListError::NotSquare {
+                row: 2,
+                len: 3,
+                num_vertices: 4
+            })
+        );
+        let mut self_loop = k4;
+        self_loop[2][2] = true;
+        assert_eq!(
+            Graph::from_adjacency_matrix(&self_loop),
+            Err(EdgeListError::SelfLoop(2))
+        );
+    }
+}
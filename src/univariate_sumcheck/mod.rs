@@ -0,0 +1,222 @@
+use std::fmt;
+
+use ark_ff::{FftField, Field, Zero};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial};
+use ark_std::rand::RngCore;
+
+use crate::lagrange::univariate::univariate::interpolate_at_points;
+use crate::sumcheck::oracle::{Oracle, OracleError};
+
+/// Univariate sumcheck (Aurora, Marlin): prove `sum_{h in H} f(h) = c` for a multiplicative subgroup `H`.
+///
+/// Dividing `f` by the vanishing polynomial `Z_H` leaves `f = h * Z_H + r` with `deg r < |H|`. Since
+/// `sum_{h in H} h^k = 0` for `0 < k < |H|`, only the constant term of `r` survives the sum, so
+/// `r = x * g + c / |H|` and the prover sends `h` and `g`. The verifier checks the decomposition at
+/// one random point and bounds the degrees of `h` and `g`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnivariateSumcheckProof<F: Field> {
+    pub h: DensePolynomial<F>,
+    pub g: DensePolynomial<F>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnivariateSumcheckError {
+    /// `h` or `g` exceeds the degree the decomposition allows.
+    DegreeTooHigh,
+    /// `f(z) != h(z) * Z_H(z) + z * g(z) + c / |H|` at the random point `z`.
+    DecompositionMismatch,
+    Oracle(OracleError),
+}
+
+impl fmt::Display for UnivariateSumcheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnivariateSumcheckError::DegreeTooHigh => write!(f, "proof polynomial degree too high"),
+            UnivariateSumcheckError::DecompositionMismatch => {
+                write!(f, "f does not decompose as claimed")
+            }
+            UnivariateSumcheckError::Oracle(e) => write!(f, "oracle error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for UnivariateSumcheckError {}
+
+impl From<OracleError> for UnivariateSumcheckError {
+    fn from(e: OracleError) -> Self {
+        UnivariateSumcheckError::Oracle(e)
+    }
+}
+
+pub struct Prover<F: FftField, D: EvaluationDomain<F>> {
+    f: DensePolynomial<F>,
+    domain: D,
+}
+
+impl<F: FftField, D: EvaluationDomain<F>> Prover<F, D> {
+    pub fn init(f: DensePolynomial<F>, domain: D) -> Self {
+        Prover { f, domain }
+    }
+
+    /// `f` given by its values on `domain`, interpolated with the `lagrange::univariate` module.
+    pub fn from_values(values: &[F], domain: D) -> Self {
+        let points: Vec<F> = domain.elements().collect();
+        Prover {
+            f: interpolate_at_points(&points, values),
+            domain,
+        }
+    }
+
+    /// The sum `c = sum_{h in H} f(h)`.
+    pub fn claim(&self) -> F {
+        let (_, remainder) = self.decompose();
+        remainder.coeffs.first().copied().unwrap_or_else(F::zero)
+            * self.domain.size_as_field_element()
+    }
+
+    pub fn prove(&self) -> UnivariateSumcheckProof<F> {
+        let (h, remainder) = self.decompose();
+        // r = x * g + r_0, so g is r without its constant term
+        let g = DensePolynomial::from_coefficients_vec(
+            remainder.coeffs.iter().skip(1).copied().collect(),
+        );
+        UnivariateSumcheckProof { h, g }
+    }
+
+    fn decompose(&self) -> (DensePolynomial<F>, DensePolynomial<F>) {
+        self.f
+            .divide_by_vanishing_poly(self.domain)
+            .expect("division by the vanishing polynomial should not fail")
+    }
+}
+
+pub struct Verifier<F: FftField, D: EvaluationDomain<F>> {
+    claim: F,
+    domain: D,
+    // the degree bound on `f`, needed to bound `h`
+    max_degree: usize,
+}
+
+impl<F: FftField, D: EvaluationDomain<F>> Verifier<F, D> {
+    pub fn init(claim: F, domain: D, max_degree: usize) -> Self {
+        Verifier {
+            claim,
+            domain,
+            max_degree,
+        }
+    }
+
+    /// Check the proof with a single query to `f` at a random point outside of `H`.
+    pub fn verify<O: Oracle<F>>(
+        &self,
+        proof: &UnivariateSumcheckProof<F>,
+        f: &O,
+        rng: &mut dyn RngCore,
+    ) -> Result<(), UnivariateSumcheckError> {
+        let size = self.domain.size();
+        // deg g <= |H| - 2 and deg h <= deg f - |H|
+        if !proof.g.is_zero() && proof.g.degree() + 2 > size {
+            return Err(UnivariateSumcheckError::DegreeTooHigh);
+        }
+        if !proof.h.is_zero() && proof.h.degree() + size > self.max_degree {
+            return Err(UnivariateSumcheckError::DegreeTooHigh);
+        }
+
+        // a point of H makes Z_H(z) = 0, which would let h be anything
+        let z = loop {
+            let z = F::rand(rng);
+            if !self.domain.evaluate_vanishing_polynomial(z).is_zero() {
+                break z;
+            }
+        };
+        let expected = Polynomial::evaluate(&proof.h, &z)
+            * self.domain.evaluate_vanishing_polynomial(z)
+            + z * Polynomial::evaluate(&proof.g, &z)
+            + self.claim / self.domain.size_as_field_element();
+        if f.evaluate(&[z])? != expected {
+            return Err(UnivariateSumcheckError::DecompositionMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{Fp64, MontBackend, MontConfig, One, UniformRand};
+    use ark_poly::Radix2EvaluationDomain;
+    use ark_std::test_rng;
+
+    use super::*;
+
+    // 96 = 2^5 * 3, so there are multiplicative subgroups of size up to 32
+    #[derive(MontConfig)]
+    #[modulus = "97"]
+    #[generator = "5"]
+    pub struct FqConfig;
+    pub type Fq = Fp64<MontBackend<FqConfig, 1>>;
+
+    fn brute_force_sum(f: &DensePolynomial<Fq>, domain: &Radix2EvaluationDomain<Fq>) -> Fq {
+        domain.elements().map(|h| Polynomial::evaluate(f, &h)).sum()
+    }
+
+    #[test]
+    fn honest_proof_verifies() {
+        let rng = &mut test_rng();
+        let domain = Radix2EvaluationDomain::<Fq>::new(8).unwrap();
+        let f = DensePolynomial::<Fq>::rand(20, rng);
+
+        let prover = Prover::init(f.clone(), domain);
+        let claim = prover.claim();
+        assert_eq!(claim, brute_force_sum(&f, &domain));
+
+        let proof = prover.prove();
+        let verifier = Verifier::init(claim, domain, 20);
+        verifier.verify(&proof, &f, rng).unwrap();
+    }
+
+    #[test]
+    fn wrong_claim_rejected() {
+        let rng = &mut test_rng();
+        let domain = Radix2EvaluationDomain::<Fq>::new(8).unwrap();
+        let f = DensePolynomial::<Fq>::rand(20, rng);
+        let prover = Prover::init(f.clone(), domain);
+        let proof = prover.prove();
+
+        let verifier = Verifier::init(prover.claim() + Fq::one(), domain, 20);
+        assert_eq!(
+            verifier.verify(&proof, &f, rng),
+            Err(UnivariateSumcheckError::DecompositionMismatch)
+        );
+    }
+
+    #[test]
+    fn degree_bounds_enforced() {
+        let rng = &mut test_rng();
+        let domain = Radix2EvaluationDomain::<Fq>::new(4).unwrap();
+        let f = DensePolynomial::<Fq>::rand(10, rng);
+        let prover = Prover::init(f.clone(), domain);
+        let mut proof = prover.prove();
+        // g of degree |H| - 1 could hide a nonzero sum in its top coefficient
+        proof.g = DensePolynomial::from_coefficients_vec(vec![
+            Fq::zero(),
+            Fq::zero(),
+            Fq::zero(),
+            Fq::rand(rng),
+        ]);
+        let verifier = Verifier::init(prover.claim(), domain, 10);
+        assert_eq!(
+            verifier.verify(&proof, &f, rng),
+            Err(UnivariateSumcheckError::DegreeTooHigh)
+        );
+    }
+
+    #[test]
+    fn from_values_sums_values() {
+        let domain = Radix2EvaluationDomain::<Fq>::new(8).unwrap();
+        let values: Vec<Fq> = (1..=8u64).map(Fq::from).collect();
+        let prover = Prover::from_values(&values, domain);
+        assert_eq!(prover.claim(), Fq::from(36));
+        let proof = prover.prove();
+        assert!(proof.h.is_zero());
+    }
+}
@@ -0,0 +1,163 @@
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+use ark_std::rand::RngCore;
+
+use crate::lagrange::multivariate::multivariate::{eq, DenseMLE};
+use crate::sumcheck::{
+    PolynomialInfo, Prover, SubClaim, SumcheckError, SumcheckPolynomial, Verifier,
+    VirtualPolynomial,
+};
+
+/// `eq(x, r) * g(x)` as a virtual polynomial: the eq table is added once and appended to every product of `g`.
+fn multiply_by_eq<F: Field>(g: &VirtualPolynomial<F>, r: &[F]) -> VirtualPolynomial<F> {
+    let mut product = VirtualPolynomial::new(g.num_vars());
+    for mle in g.mles() {
+        product.add_mle(mle.clone());
+    }
+    let eq_index = product.add_mle(DenseMLE::eq_table(r));
+    for (coefficient, indices) in g.products() {
+        let mut indices = indices.clone();
+        indices.push(eq_index);
+        product.add_product(*coefficient, indices);
+    }
+    product
+}
+
+/// Proves that `g` vanishes on the whole hypercube by running sumcheck on `sum_x eq(x, r) * g(x) = 0`.
+///
+/// `sum_x eq(x, r) * g(x)` is the multilinear extension of `g`'s table evaluated at `r`, which is the
+/// zero polynomial iff `g` is zero on every `x` in {0,1}^n. For a random `r` a nonzero extension
+/// vanishes with probability at most `n / |F|`.
+pub struct ZeroCheckProver<F: Field> {
+    prover: Prover<F, VirtualPolynomial<F>>,
+}
+
+impl<F: Field> ZeroCheckProver<F> {
+    /// `r` is the verifier's random point, see `ZeroCheckVerifier::r`.
+    pub fn init(g: &VirtualPolynomial<F>, r: &[F]) -> Result<Self, SumcheckError> {
+        if r.len() != g.num_vars() {
+            return Err(SumcheckError::NumVarsMismatch {
+                declared: g.num_vars(),
+                actual: r.len(),
+            });
+        }
+        Ok(ZeroCheckProver {
+            prover: Prover::init(multiply_by_eq(g, r))?,
+        })
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.prover.num_vars()
+    }
+
+    pub fn prove_round(&mut self, r: Option<F>) -> DensePolynomial<F> {
+        self.prover.prove_round(r)
+    }
+}
+
+pub struct ZeroCheckVerifier<F: Field> {
+    verifier: Verifier<F>,
+    r: Vec<F>,
+}
+
+impl<F: Field> ZeroCheckVerifier<F> {
+    /// `info` describes `g`; the eq factor adds one to its degree in every variable.
+    pub fn init(info: PolynomialInfo, rng: &mut dyn RngCore) -> Self {
+        let r = (0..info.num_vars).map(|_| F::rand(rng)).collect();
+        ZeroCheckVerifier {
+            verifier: Verifier::init_deferred(
+                F::zero(),
+                PolynomialInfo {
+                    num_vars: info.num_vars,
+                    max_degree: info.max_degree + 1,
+                },
+            ),
+            r,
+        }
+    }
+
+    /// The random point of the eq factor, sent to the prover before the first round.
+    pub fn r(&self) -> &[F] {
+        &self.r
+    }
+
+    pub fn verify_round(
+        &mut self,
+        current_poly: DensePolynomial<F>,
+        rng: &mut dyn RngCore,
+    ) -> Result<Option<F>, SumcheckError> {
+        self.verifier.verify_round(current_poly, rng)
+    }
+
+    /// Turn sumcheck's claim `eq(point, r) * g(point) = v` into the claim `g(point) = v / eq(point, r)`.
+    /// The verifier evaluates `eq` itself, so only `g` is left for the caller to check.
+    pub fn subclaim(&self) -> Result<SubClaim<F>, SumcheckError> {
+        let subclaim = self
+            .verifier
+            .subclaim()
+            .ok_or(SumcheckError::ProtocolNotFinished)?;
+        // eq(point, r) = 0 only happens with probability n / |F|, and then nothing can be learned about g
+        let eq_inverse = eq(&subclaim.point, &self.r)
+            .inverse()
+            .ok_or(SumcheckError::FinalCheckFailed)?;
+        Ok(SubClaim {
+            point: subclaim.point.clone(),
+            expected_value: subclaim.expected_value * eq_inverse,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{One, UniformRand};
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::sumcheck::test_utils::Fq;
+
+    // g = a * b - c, which vanishes on the hypercube iff c is the entrywise product of a and b
+    fn multiplication_gates(num_vars: usize, break_gate: bool) -> VirtualPolynomial<Fq> {
+        let rng = &mut test_rng();
+        let a: Vec<Fq> = (0..1 << num_vars).map(|_| Fq::rand(rng)).collect();
+        let b: Vec<Fq> = (0..1 << num_vars).map(|_| Fq::rand(rng)).collect();
+        let mut c: Vec<Fq> = a.iter().zip(&b).map(|(x, y)| *x * y).collect();
+        if break_gate {
+            c[3] += Fq::one();
+        }
+
+        let mut g = VirtualPolynomial::new(num_vars);
+        let a = g.add_mle(DenseMLE::new(num_vars, a));
+        let b = g.add_mle(DenseMLE::new(num_vars, b));
+        let c = g.add_mle(DenseMLE::new(num_vars, c));
+        g.add_product(Fq::one(), vec![a, b]);
+        g.add_product(-Fq::one(), vec![c]);
+        g
+    }
+
+    fn run_zerocheck(g: &VirtualPolynomial<Fq>) -> Result<SubClaim<Fq>, SumcheckError> {
+        let rng = &mut test_rng();
+        let mut verifier = ZeroCheckVerifier::init(g.info(), rng);
+        let mut prover = ZeroCheckProver::init(g, verifier.r())?;
+        let mut r = None;
+        for _ in 0..prover.num_vars() {
+            r = verifier.verify_round(prover.prove_round(r), rng)?;
+        }
+        verifier.subclaim()
+    }
+
+    #[test]
+    fn zerocheck_accepts_vanishing_polynomial() {
+        let g = multiplication_gates(4, false);
+        let subclaim = run_zerocheck(&g).unwrap();
+        assert!(subclaim.check(&g).unwrap());
+    }
+
+    #[test]
+    fn zerocheck_rejects_nonvanishing_polynomial() {
+        let g = multiplication_gates(4, true);
+        assert_eq!(
+            run_zerocheck(&g).err(),
+            Some(SumcheckError::ClaimMismatch { round: 0 })
+        );
+    }
+}